@@ -0,0 +1,43 @@
+use soroban_sdk::{contracttype, Address, Env};
+
+// Two steps admin transfer shared by all the contracts.
+// Each contract keeps its admin in its own storage, this module only handles the pending admin and the events so
+// contracts need to save the address returned by `accept_admin`.
+#[contracttype]
+pub enum AdminDataKeys {
+    PendingAdmin,
+}
+
+#[contracttype]
+pub enum AdminEventTopics {
+    AdminProposed,
+    AdminChanged,
+}
+
+// The current admin proposes a new admin, proposing a new one replaces the old proposal
+pub fn propose_admin(e: &Env, current_admin: &Address, new_admin: &Address) {
+    current_admin.require_auth();
+    e.storage()
+        .instance()
+        .set(&AdminDataKeys::PendingAdmin, new_admin);
+    e.events().publish(
+        (AdminEventTopics::AdminProposed,),
+        (current_admin.clone(), new_admin.clone()),
+    );
+}
+
+// The pending admin accepts the proposal, it returns `None` if there is no proposal
+pub fn accept_admin(e: &Env, current_admin: &Address) -> Option<Address> {
+    let new_admin: Address = pending_admin(e)?;
+    new_admin.require_auth();
+    e.storage().instance().remove(&AdminDataKeys::PendingAdmin);
+    e.events().publish(
+        (AdminEventTopics::AdminChanged,),
+        (current_admin.clone(), new_admin.clone()),
+    );
+    Some(new_admin)
+}
+
+pub fn pending_admin(e: &Env) -> Option<Address> {
+    e.storage().instance().get(&AdminDataKeys::PendingAdmin)
+}
@@ -0,0 +1,234 @@
+// Converts unicode labels to the canonical ascii form the registry uses for internationalized names, so clients and
+// other contracts can find the node of a name like `Café` without a full IDNA library:
+// - The letters of the scripts the registry supports are lowercased.
+// - Combining marks are composed with the letter before them (for example `e` + `U+0301` becomes `é`) and Hangul jamo
+//   are composed into syllables.
+// - Labels with non ascii characters are encoded with punycode and the `xn--` prefix (`café` becomes `xn--caf-dma`).
+// This is the part of the UTS #46 mapping and the NFC normalization that covers the characters the registry accepts,
+// other characters are kept as they are so the registry rejects them. Each letter can have one combining mark at most.
+
+use crate::punycode::{encode, PunycodeError, MAX_LABEL_LEN};
+use soroban_sdk::Bytes;
+
+pub const IDN_PREFIX: &[u8] = b"xn--";
+
+// A unicode label is at most 63 characters long and each character is at most 4 bytes long in UTF-8
+pub const MAX_UNICODE_LABEL_LEN: usize = MAX_LABEL_LEN * 4;
+
+// Canonical compositions (letter, combining mark, composed letter) of the lowercase letters the registry accepts, taken
+// from the Unicode decomposition data and sorted by letter and mark
+#[rustfmt::skip]
+const COMPOSITIONS: [(u16, u16, u16); 159] = [
+    (0x0061, 0x0300, 0x00E0), (0x0061, 0x0301, 0x00E1), (0x0061, 0x0302, 0x00E2), (0x0061, 0x0303, 0x00E3),
+    (0x0061, 0x0304, 0x0101), (0x0061, 0x0306, 0x0103), (0x0061, 0x0308, 0x00E4), (0x0061, 0x030A, 0x00E5),
+    (0x0061, 0x0328, 0x0105), (0x0063, 0x0301, 0x0107), (0x0063, 0x0302, 0x0109), (0x0063, 0x0307, 0x010B),
+    (0x0063, 0x030C, 0x010D), (0x0063, 0x0327, 0x00E7), (0x0064, 0x030C, 0x010F), (0x0065, 0x0300, 0x00E8),
+    (0x0065, 0x0301, 0x00E9), (0x0065, 0x0302, 0x00EA), (0x0065, 0x0304, 0x0113), (0x0065, 0x0306, 0x0115),
+    (0x0065, 0x0307, 0x0117), (0x0065, 0x0308, 0x00EB), (0x0065, 0x030C, 0x011B), (0x0065, 0x0328, 0x0119),
+    (0x0067, 0x0302, 0x011D), (0x0067, 0x0306, 0x011F), (0x0067, 0x0307, 0x0121), (0x0067, 0x0327, 0x0123),
+    (0x0068, 0x0302, 0x0125), (0x0069, 0x0300, 0x00EC), (0x0069, 0x0301, 0x00ED), (0x0069, 0x0302, 0x00EE),
+    (0x0069, 0x0303, 0x0129), (0x0069, 0x0304, 0x012B), (0x0069, 0x0306, 0x012D), (0x0069, 0x0308, 0x00EF),
+    (0x0069, 0x0328, 0x012F), (0x006A, 0x0302, 0x0135), (0x006B, 0x0327, 0x0137), (0x006C, 0x0301, 0x013A),
+    (0x006C, 0x030C, 0x013E), (0x006C, 0x0327, 0x013C), (0x006E, 0x0301, 0x0144), (0x006E, 0x0303, 0x00F1),
+    (0x006E, 0x030C, 0x0148), (0x006E, 0x0327, 0x0146), (0x006F, 0x0300, 0x00F2), (0x006F, 0x0301, 0x00F3),
+    (0x006F, 0x0302, 0x00F4), (0x006F, 0x0303, 0x00F5), (0x006F, 0x0304, 0x014D), (0x006F, 0x0306, 0x014F),
+    (0x006F, 0x0308, 0x00F6), (0x006F, 0x030B, 0x0151), (0x0072, 0x0301, 0x0155), (0x0072, 0x030C, 0x0159),
+    (0x0072, 0x0327, 0x0157), (0x0073, 0x0301, 0x015B), (0x0073, 0x0302, 0x015D), (0x0073, 0x030C, 0x0161),
+    (0x0073, 0x0327, 0x015F), (0x0074, 0x030C, 0x0165), (0x0074, 0x0327, 0x0163), (0x0075, 0x0300, 0x00F9),
+    (0x0075, 0x0301, 0x00FA), (0x0075, 0x0302, 0x00FB), (0x0075, 0x0303, 0x0169), (0x0075, 0x0304, 0x016B),
+    (0x0075, 0x0306, 0x016D), (0x0075, 0x0308, 0x00FC), (0x0075, 0x030A, 0x016F), (0x0075, 0x030B, 0x0171),
+    (0x0075, 0x0328, 0x0173), (0x0077, 0x0302, 0x0175), (0x0079, 0x0301, 0x00FD), (0x0079, 0x0302, 0x0177),
+    (0x0079, 0x0308, 0x00FF), (0x007A, 0x0301, 0x017A), (0x007A, 0x0307, 0x017C), (0x007A, 0x030C, 0x017E),
+    (0x03B1, 0x0301, 0x03AC), (0x03B5, 0x0301, 0x03AD), (0x03B7, 0x0301, 0x03AE), (0x03B9, 0x0301, 0x03AF),
+    (0x03B9, 0x0308, 0x03CA), (0x03BF, 0x0301, 0x03CC), (0x03C5, 0x0301, 0x03CD), (0x03C5, 0x0308, 0x03CB),
+    (0x03C9, 0x0301, 0x03CE), (0x03CB, 0x0301, 0x03B0), (0x0433, 0x0301, 0x0453), (0x0435, 0x0300, 0x0450),
+    (0x0435, 0x0308, 0x0451), (0x0438, 0x0300, 0x045D), (0x0438, 0x0306, 0x0439), (0x043A, 0x0301, 0x045C),
+    (0x0443, 0x0306, 0x045E), (0x0456, 0x0308, 0x0457), (0x0627, 0x0653, 0x0622), (0x0627, 0x0654, 0x0623),
+    (0x0627, 0x0655, 0x0625), (0x0648, 0x0654, 0x0624), (0x064A, 0x0654, 0x0626), (0x3046, 0x3099, 0x3094),
+    (0x304B, 0x3099, 0x304C), (0x304D, 0x3099, 0x304E), (0x304F, 0x3099, 0x3050), (0x3051, 0x3099, 0x3052),
+    (0x3053, 0x3099, 0x3054), (0x3055, 0x3099, 0x3056), (0x3057, 0x3099, 0x3058), (0x3059, 0x3099, 0x305A),
+    (0x305B, 0x3099, 0x305C), (0x305D, 0x3099, 0x305E), (0x305F, 0x3099, 0x3060), (0x3061, 0x3099, 0x3062),
+    (0x3064, 0x3099, 0x3065), (0x3066, 0x3099, 0x3067), (0x3068, 0x3099, 0x3069), (0x306F, 0x3099, 0x3070),
+    (0x306F, 0x309A, 0x3071), (0x3072, 0x3099, 0x3073), (0x3072, 0x309A, 0x3074), (0x3075, 0x3099, 0x3076),
+    (0x3075, 0x309A, 0x3077), (0x3078, 0x3099, 0x3079), (0x3078, 0x309A, 0x307A), (0x307B, 0x3099, 0x307C),
+    (0x307B, 0x309A, 0x307D), (0x30A6, 0x3099, 0x30F4), (0x30AB, 0x3099, 0x30AC), (0x30AD, 0x3099, 0x30AE),
+    (0x30AF, 0x3099, 0x30B0), (0x30B1, 0x3099, 0x30B2), (0x30B3, 0x3099, 0x30B4), (0x30B5, 0x3099, 0x30B6),
+    (0x30B7, 0x3099, 0x30B8), (0x30B9, 0x3099, 0x30BA), (0x30BB, 0x3099, 0x30BC), (0x30BD, 0x3099, 0x30BE),
+    (0x30BF, 0x3099, 0x30C0), (0x30C1, 0x3099, 0x30C2), (0x30C4, 0x3099, 0x30C5), (0x30C6, 0x3099, 0x30C7),
+    (0x30C8, 0x3099, 0x30C9), (0x30CF, 0x3099, 0x30D0), (0x30CF, 0x309A, 0x30D1), (0x30D2, 0x3099, 0x30D3),
+    (0x30D2, 0x309A, 0x30D4), (0x30D5, 0x3099, 0x30D6), (0x30D5, 0x309A, 0x30D7), (0x30D8, 0x3099, 0x30D9),
+    (0x30D8, 0x309A, 0x30DA), (0x30DB, 0x3099, 0x30DC), (0x30DB, 0x309A, 0x30DD), (0x30EF, 0x3099, 0x30F7),
+    (0x30F0, 0x3099, 0x30F8), (0x30F1, 0x3099, 0x30F9), (0x30F2, 0x3099, 0x30FA),
+];
+
+fn lowercase(code_point: u32) -> u32 {
+    match code_point {
+        0x41..=0x5A | 0xC0..=0xD6 | 0xD8..=0xDE => code_point + 0x20,
+        // Latin Extended-A pairs the uppercase and the lowercase letters (`İ` doesn't have a single lowercase letter)
+        0x0100..=0x012F | 0x0132..=0x0137 | 0x014A..=0x0177 if code_point.is_multiple_of(2) => {
+            code_point + 1
+        }
+        0x0139..=0x0148 | 0x0179..=0x017E if code_point % 2 == 1 => code_point + 1,
+        0x0178 => 0xFF,
+        0x0386 => 0x03AC,
+        0x0388..=0x038A => code_point + 0x25,
+        0x038C => 0x03CC,
+        0x038E | 0x038F => code_point + 0x3F,
+        0x0391..=0x03A1 | 0x03A3..=0x03AB => code_point + 0x20,
+        0x0400..=0x040F => code_point + 0x50,
+        0x0410..=0x042F => code_point + 0x20,
+        _ => code_point,
+    }
+}
+
+fn compose(letter: u32, mark: u32) -> Option<u32> {
+    match (letter, mark) {
+        // Leading and vowel jamo
+        (0x1100..=0x1112, 0x1161..=0x1175) => {
+            Some(0xAC00 + ((letter - 0x1100) * 21 + (mark - 0x1161)) * 28)
+        }
+        // Syllable without a trailing jamo and a trailing jamo
+        (0xAC00..=0xD7A3, 0x11A8..=0x11C2) if (letter - 0xAC00).is_multiple_of(28) => {
+            Some(letter + mark - 0x11A7)
+        }
+        _ => COMPOSITIONS
+            .binary_search_by(|(l, m, _)| (*l as u32, *m as u32).cmp(&(letter, mark)))
+            .ok()
+            .map(|index| COMPOSITIONS[index].2 as u32),
+    }
+}
+
+// Converts an UTF-8 label into its canonical ascii form, it returns the amount of bytes written in the output.
+// Ascii labels are only lowercased so labels already in their canonical form are returned as they are.
+pub fn to_ascii(input: &[u8], output: &mut [u8; MAX_LABEL_LEN]) -> Result<usize, PunycodeError> {
+    let label: &str = core::str::from_utf8(input).map_err(|_| PunycodeError::InvalidInput)?;
+
+    let mut code_points: [u32; MAX_LABEL_LEN] = [0; MAX_LABEL_LEN];
+    let mut len: usize = 0;
+    for character in label.chars() {
+        let code_point: u32 = lowercase(character as u32);
+        if len > 0 {
+            if let Some(composed) = compose(code_points[len - 1], code_point) {
+                code_points[len - 1] = composed;
+                continue;
+            }
+        }
+
+        if len == MAX_LABEL_LEN {
+            return Err(PunycodeError::TooLong);
+        }
+        code_points[len] = code_point;
+        len += 1;
+    }
+
+    if code_points[..len]
+        .iter()
+        .all(|code_point| *code_point < 0x80)
+    {
+        for (index, code_point) in code_points[..len].iter().enumerate() {
+            output[index] = *code_point as u8;
+        }
+        return Ok(len);
+    }
+
+    let mut encoded: [u8; MAX_LABEL_LEN] = [0; MAX_LABEL_LEN];
+    let encoded_len: usize = encode(&code_points[..len], &mut encoded)?;
+    let output_len: usize = IDN_PREFIX.len() + encoded_len;
+    if output_len > MAX_LABEL_LEN {
+        return Err(PunycodeError::TooLong);
+    }
+
+    output[..IDN_PREFIX.len()].copy_from_slice(IDN_PREFIX);
+    output[IDN_PREFIX.len()..output_len].copy_from_slice(&encoded[..encoded_len]);
+    Ok(output_len)
+}
+
+// Same as `to_ascii` but for the labels contracts receive as `Bytes`
+pub fn label_to_ascii(label: &Bytes) -> Result<Bytes, PunycodeError> {
+    let input_len: usize = label.len() as usize;
+    if input_len > MAX_UNICODE_LABEL_LEN {
+        return Err(PunycodeError::TooLong);
+    }
+
+    let mut input: [u8; MAX_UNICODE_LABEL_LEN] = [0; MAX_UNICODE_LABEL_LEN];
+    label.copy_into_slice(&mut input[..input_len]);
+
+    let mut output: [u8; MAX_LABEL_LEN] = [0; MAX_LABEL_LEN];
+    let output_len: usize = to_ascii(&input[..input_len], &mut output)?;
+    Ok(Bytes::from_slice(label.env(), &output[..output_len]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{label_to_ascii, to_ascii, COMPOSITIONS, MAX_LABEL_LEN};
+    use crate::punycode::PunycodeError;
+    use soroban_sdk::{Bytes, Env};
+
+    fn assert_ascii(label: &str, expected: &str) {
+        let mut output: [u8; MAX_LABEL_LEN] = [0; MAX_LABEL_LEN];
+        let len: usize = to_ascii(label.as_bytes(), &mut output).unwrap();
+        assert_eq!(&output[..len], expected.as_bytes(), "{}", label);
+    }
+
+    #[test]
+    fn test_compositions_are_sorted() {
+        for pair in COMPOSITIONS.windows(2) {
+            assert!((pair[0].0, pair[0].1) < (pair[1].0, pair[1].1));
+        }
+    }
+
+    #[test]
+    fn test_to_ascii() {
+        for (label, expected) in [
+            ("stellar", "stellar"),
+            ("Stellar", "stellar"),
+            ("xn--caf-dma", "xn--caf-dma"),
+            ("café", "xn--caf-dma"),
+            ("CAFÉ", "xn--caf-dma"),
+            // `e` followed by a combining acute accent
+            ("cafe\u{301}", "xn--caf-dma"),
+            ("Cafe\u{301}", "xn--caf-dma"),
+            ("AĞAÇ", "xn--aa-5ia6t"),
+            ("ΑΘΉΝΑ", "xn--jxafb0a0a"),
+            ("ЁЛКА", "xn--80atc1g"),
+            ("е\u{308}лка", "xn--80atc1g"),
+            // Hangul jamo and a kana with a combining voiced sound mark
+            (
+                "\u{1112}\u{1161}\u{11AB}\u{1100}\u{1173}\u{11AF}",
+                "xn--bj0bj06e",
+            ),
+            ("か\u{3099}", "xn--v8j"),
+        ] {
+            assert_ascii(label, expected);
+        }
+    }
+
+    #[test]
+    fn test_to_ascii_errors() {
+        let mut output: [u8; MAX_LABEL_LEN] = [0; MAX_LABEL_LEN];
+        assert_eq!(
+            to_ascii(&[0x63, 0xFF], &mut output),
+            Err(PunycodeError::InvalidInput)
+        );
+
+        // The punycode form of 63 `é` doesn't fit in a label
+        let mut long: [u8; MAX_LABEL_LEN * 2] = [0; MAX_LABEL_LEN * 2];
+        for character in long.chunks_mut(2) {
+            character.copy_from_slice("é".as_bytes());
+        }
+        assert_eq!(to_ascii(&long, &mut output), Err(PunycodeError::TooLong));
+        assert_eq!(
+            to_ascii(&[b'a'; MAX_LABEL_LEN + 1], &mut output),
+            Err(PunycodeError::TooLong)
+        );
+    }
+
+    #[test]
+    fn test_label_to_ascii() {
+        let e: Env = Env::default();
+        assert_eq!(
+            label_to_ascii(&Bytes::from_slice(&e, "Café".as_bytes())),
+            Ok(Bytes::from_slice(&e, b"xn--caf-dma"))
+        );
+    }
+}
@@ -1,3 +1,6 @@
 #![no_std]
 
+pub mod admin;
+pub mod idna;
+pub mod punycode;
 pub mod utils;
@@ -0,0 +1,366 @@
+// Punycode (RFC 3492) encoder and decoder used for internationalized domain names.
+// Labels are at most 63 bytes long (the DNS limit) so both functions work with fixed size buffers and don't need an
+// allocator. The `xn--` prefix is not part of the input or the output.
+
+pub const MAX_LABEL_LEN: usize = 63;
+
+const BASE: u32 = 36;
+const T_MIN: u32 = 1;
+const T_MAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+const DELIMITER: u8 = b'-';
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PunycodeError {
+    // The input is not valid punycode (invalid digits, overflows or non basic code points before the delimiter)
+    InvalidInput,
+
+    // The output doesn't fit in a label
+    TooLong,
+}
+
+fn adapt(delta: u32, num_points: u32, first_time: bool) -> u32 {
+    let mut delta: u32 = if first_time { delta / DAMP } else { delta / 2 };
+    delta += delta / num_points;
+
+    let mut k: u32 = 0;
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+
+    k + (((BASE - T_MIN + 1) * delta) / (delta + SKEW))
+}
+
+fn threshold(k: u32, bias: u32) -> u32 {
+    if k <= bias {
+        T_MIN
+    } else if k >= bias + T_MAX {
+        T_MAX
+    } else {
+        k - bias
+    }
+}
+
+// Only lowercase digits are accepted so every label has a single valid encoding
+fn decode_digit(byte: u8) -> Option<u32> {
+    match byte {
+        b'a'..=b'z' => Some((byte - b'a') as u32),
+        b'0'..=b'9' => Some((byte - b'0') as u32 + 26),
+        _ => None,
+    }
+}
+
+fn encode_digit(digit: u32) -> u8 {
+    if digit < 26 {
+        b'a' + digit as u8
+    } else {
+        b'0' + (digit - 26) as u8
+    }
+}
+
+// Decodes a punycode label into code points, it returns the amount of code points written in the output
+pub fn decode(input: &[u8], output: &mut [u32; MAX_LABEL_LEN]) -> Result<usize, PunycodeError> {
+    let basic_len: usize = input
+        .iter()
+        .rposition(|byte| *byte == DELIMITER)
+        .unwrap_or(0);
+
+    if basic_len > MAX_LABEL_LEN {
+        return Err(PunycodeError::TooLong);
+    }
+
+    for (index, byte) in input[..basic_len].iter().enumerate() {
+        if *byte >= 0x80 {
+            return Err(PunycodeError::InvalidInput);
+        }
+        output[index] = *byte as u32;
+    }
+
+    let mut len: usize = basic_len;
+    let mut n: u32 = INITIAL_N;
+    let mut i: u32 = 0;
+    let mut bias: u32 = INITIAL_BIAS;
+    let mut position: usize = if basic_len > 0 { basic_len + 1 } else { 0 };
+
+    while position < input.len() {
+        let old_i: u32 = i;
+        let mut w: u32 = 1;
+        let mut k: u32 = BASE;
+
+        loop {
+            let digit: u32 = input
+                .get(position)
+                .and_then(|byte| decode_digit(*byte))
+                .ok_or(PunycodeError::InvalidInput)?;
+            position += 1;
+
+            i = digit
+                .checked_mul(w)
+                .and_then(|value| i.checked_add(value))
+                .ok_or(PunycodeError::InvalidInput)?;
+
+            let t: u32 = threshold(k, bias);
+            if digit < t {
+                break;
+            }
+
+            w = w.checked_mul(BASE - t).ok_or(PunycodeError::InvalidInput)?;
+            k += BASE;
+        }
+
+        if len >= MAX_LABEL_LEN {
+            return Err(PunycodeError::TooLong);
+        }
+
+        let points: u32 = len as u32 + 1;
+        bias = adapt(i - old_i, points, old_i == 0);
+        n = n
+            .checked_add(i / points)
+            .ok_or(PunycodeError::InvalidInput)?;
+        i %= points;
+
+        if !(INITIAL_N..=0x10FFFF).contains(&n) || (0xD800..=0xDFFF).contains(&n) {
+            return Err(PunycodeError::InvalidInput);
+        }
+
+        let index: usize = i as usize;
+        output.copy_within(index..len, index + 1);
+        output[index] = n;
+        len += 1;
+        i += 1;
+    }
+
+    Ok(len)
+}
+
+// Encodes code points into a punycode label, it returns the amount of bytes written in the output
+pub fn encode(input: &[u32], output: &mut [u8; MAX_LABEL_LEN]) -> Result<usize, PunycodeError> {
+    let mut len: usize = 0;
+    let mut push = |byte: u8, len: &mut usize| -> Result<(), PunycodeError> {
+        if *len >= MAX_LABEL_LEN {
+            return Err(PunycodeError::TooLong);
+        }
+        output[*len] = byte;
+        *len += 1;
+        Ok(())
+    };
+
+    for code_point in input.iter().filter(|code_point| **code_point < 0x80) {
+        push(*code_point as u8, &mut len)?;
+    }
+
+    let basic_len: u32 = len as u32;
+    if basic_len > 0 {
+        push(DELIMITER, &mut len)?;
+    }
+
+    let mut n: u32 = INITIAL_N;
+    let mut delta: u32 = 0;
+    let mut bias: u32 = INITIAL_BIAS;
+    let mut handled: u32 = basic_len;
+
+    while (handled as usize) < input.len() {
+        let m: u32 = input
+            .iter()
+            .filter(|code_point| **code_point >= n)
+            .min()
+            .copied()
+            .ok_or(PunycodeError::InvalidInput)?;
+
+        delta = (m - n)
+            .checked_mul(handled + 1)
+            .and_then(|value| delta.checked_add(value))
+            .ok_or(PunycodeError::InvalidInput)?;
+        n = m;
+
+        for code_point in input.iter() {
+            if *code_point < n {
+                delta = delta.checked_add(1).ok_or(PunycodeError::InvalidInput)?;
+            }
+
+            if *code_point == n {
+                let mut q: u32 = delta;
+                let mut k: u32 = BASE;
+                loop {
+                    let t: u32 = threshold(k, bias);
+                    if q < t {
+                        break;
+                    }
+                    push(encode_digit(t + ((q - t) % (BASE - t))), &mut len)?;
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                push(encode_digit(q), &mut len)?;
+
+                bias = adapt(delta, handled + 1, handled == basic_len);
+                delta = 0;
+                handled += 1;
+            }
+        }
+
+        delta += 1;
+        n += 1;
+    }
+
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode, PunycodeError, MAX_LABEL_LEN};
+
+    // Sample strings from RFC 3492 section 7.1
+    const VECTORS: [(&[u32], &str); 7] = [
+        (
+            // (A) Arabic (Egyptian)
+            &[
+                0x0644, 0x064A, 0x0647, 0x0645, 0x0627, 0x0628, 0x062A, 0x0643, 0x0644, 0x0645,
+                0x0648, 0x0634, 0x0639, 0x0631, 0x0628, 0x064A, 0x061F,
+            ],
+            "egbpdaj6bu4bxfgehfvwxn",
+        ),
+        (
+            // (B) Chinese (simplified)
+            &[
+                0x4ED6, 0x4EEC, 0x4E3A, 0x4EC0, 0x4E48, 0x4E0D, 0x8BF4, 0x4E2D, 0x6587,
+            ],
+            "ihqwcrb4cv8a8dqg056pqjye",
+        ),
+        (
+            // (C) Chinese (traditional)
+            &[
+                0x4ED6, 0x5011, 0x7232, 0x4EC0, 0x9EBD, 0x4E0D, 0x8AAA, 0x4E2D, 0x6587,
+            ],
+            "ihqwctvzc91f659drss3x8bo0yb",
+        ),
+        (
+            // (D) Czech
+            &[
+                0x0050, 0x0072, 0x006F, 0x010D, 0x0070, 0x0072, 0x006F, 0x0073, 0x0074, 0x011B,
+                0x006E, 0x0065, 0x006D, 0x006C, 0x0075, 0x0076, 0x00ED, 0x010D, 0x0065, 0x0073,
+                0x006B, 0x0079,
+            ],
+            "Proprostnemluvesky-uyb24dma41a",
+        ),
+        (
+            // (L) 3<nen>B<gumi><kinpachi><sensei>
+            &[
+                0x0033, 0x5E74, 0x0042, 0x7D44, 0x91D1, 0x516B, 0x5148, 0x751F,
+            ],
+            "3B-ww4c5e180e575a65lsy2b",
+        ),
+        (
+            // (R) <sono><supiido><de>
+            &[0x305D, 0x306E, 0x30B9, 0x30D4, 0x30FC, 0x30C9, 0x3067],
+            "d9juau41awczczp",
+        ),
+        (
+            // (S) -> $1.00 <-
+            &[
+                0x002D, 0x003E, 0x0020, 0x0024, 0x0031, 0x002E, 0x0030, 0x0030, 0x0020, 0x003C,
+                0x002D,
+            ],
+            "-> $1.00 <--",
+        ),
+    ];
+
+    #[test]
+    fn test_rfc_vectors() {
+        for (code_points, punycode) in VECTORS {
+            let mut encoded: [u8; MAX_LABEL_LEN] = [0; MAX_LABEL_LEN];
+            let len: usize = encode(code_points, &mut encoded).unwrap();
+            assert_eq!(&encoded[..len], punycode.as_bytes());
+
+            let mut decoded: [u32; MAX_LABEL_LEN] = [0; MAX_LABEL_LEN];
+            let len: usize = decode(punycode.as_bytes(), &mut decoded).unwrap();
+            assert_eq!(&decoded[..len], code_points);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let labels: [&[u32]; 4] = [
+            // münchen
+            &[0x6D, 0xFC, 0x6E, 0x63, 0x68, 0x65, 0x6E],
+            // 例え
+            &[0x4F8B, 0x3048],
+            // 😀x
+            &[0x1F600, 0x78],
+            // Only basic code points
+            &[0x61, 0x62, 0x63],
+        ];
+
+        for label in labels {
+            let mut encoded: [u8; MAX_LABEL_LEN] = [0; MAX_LABEL_LEN];
+            let encoded_len: usize = encode(label, &mut encoded).unwrap();
+
+            let mut decoded: [u32; MAX_LABEL_LEN] = [0; MAX_LABEL_LEN];
+            let decoded_len: usize = decode(&encoded[..encoded_len], &mut decoded).unwrap();
+            assert_eq!(&decoded[..decoded_len], label);
+        }
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let mut decoded: [u32; MAX_LABEL_LEN] = [0; MAX_LABEL_LEN];
+
+        // Uppercase and symbols are not valid digits
+        assert_eq!(
+            decode(b"ihqwcrB4", &mut decoded),
+            Err(PunycodeError::InvalidInput)
+        );
+        assert_eq!(
+            decode(b"abc-d!", &mut decoded),
+            Err(PunycodeError::InvalidInput)
+        );
+
+        // The label ends in the middle of a code point
+        assert_eq!(
+            decode(b"abc-9", &mut decoded),
+            Err(PunycodeError::InvalidInput)
+        );
+
+        // Non basic code points before the delimiter
+        assert_eq!(
+            decode(&[0xC3, 0xBC, b'-', b'a'], &mut decoded),
+            Err(PunycodeError::InvalidInput)
+        );
+
+        // A lone delimiter is not a valid label
+        assert_eq!(decode(b"-", &mut decoded), Err(PunycodeError::InvalidInput));
+    }
+
+    #[test]
+    fn test_overflows() {
+        let mut decoded: [u32; MAX_LABEL_LEN] = [0; MAX_LABEL_LEN];
+        let mut encoded: [u8; MAX_LABEL_LEN] = [0; MAX_LABEL_LEN];
+
+        // Every digit is above the threshold so the weight keeps growing until it overflows
+        assert_eq!(
+            decode(b"999999999999999999999999", &mut decoded),
+            Err(PunycodeError::InvalidInput)
+        );
+
+        // The delta of a code point that far from the previous one doesn't fit in a u32
+        assert_eq!(
+            encode(&[0x80, u32::MAX], &mut encoded),
+            Err(PunycodeError::InvalidInput)
+        );
+
+        // Neither the input nor the output can be longer than a label
+        assert_eq!(
+            encode(&[0x61; MAX_LABEL_LEN + 1], &mut encoded),
+            Err(PunycodeError::TooLong)
+        );
+        let mut long_label: [u8; MAX_LABEL_LEN + 2] = [b'a'; MAX_LABEL_LEN + 2];
+        long_label[MAX_LABEL_LEN + 1] = b'-';
+        assert_eq!(
+            decode(&long_label, &mut decoded),
+            Err(PunycodeError::TooLong)
+        );
+    }
+}
@@ -1,8 +1,10 @@
 use crate::registry::Domain;
 use crate::types::{StorageKeys, Value};
 use crate::utils::{
-    extend, extend_key, fetch_domain, get_adm, get_key, pay_fee, remove_key, set_key,
+    extend, extend_key, fetch_domain, get_adm, get_fee, get_key, is_paused, pay_fee, remove_key,
+    set_key,
 };
+use common::admin;
 use soroban_sdk::{contract, contractimpl, panic_with_error, Address, BytesN, Env, Symbol};
 
 use crate::errors::ContractErrors;
@@ -18,6 +20,20 @@ pub trait KeyValueDBTrait {
     );
     fn upgrade(e: Env, hash: BytesN<32>);
 
+    // The admin is transferred in two steps: the current admin proposes a new admin and the new admin accepts it
+    fn propose_admin(e: Env, new_admin: Address);
+    fn accept_admin(e: Env);
+    fn admin(e: Env) -> Option<Address>;
+    fn pending_admin(e: Env) -> Option<Address>;
+
+    // The admin can pause the contract, while it's paused keys can't be set but owners can still remove their keys
+    fn pause(e: Env);
+    fn unpause(e: Env);
+    fn is_paused(e: Env) -> bool;
+
+    // The fee and its currency, it's paid to the treasury every time a key is set
+    fn fee(e: Env) -> Option<(u128, Address)>;
+
     fn set(e: Env, node: BytesN<32>, key: Symbol, value: Value);
     fn get(e: Env, node: BytesN<32>, key: Symbol) -> Option<Value>;
     fn remove(e: Env, node: BytesN<32>, key: Symbol);
@@ -38,6 +54,11 @@ impl KeyValueDBTrait for KeyValueDB {
     ) {
         if let Some(current_adm) = get_adm(&e) {
             current_adm.require_auth();
+
+            // Once the admin is set it can only be changed with `propose_admin` and `accept_admin`
+            if current_adm != adm {
+                panic_with_error!(&e, &ContractErrors::InvalidAdmin);
+            }
         }
 
         e.storage().instance().set(&StorageKeys::Admin, &adm);
@@ -61,7 +82,55 @@ impl KeyValueDBTrait for KeyValueDB {
         extend(&e);
     }
 
+    fn propose_admin(e: Env, new_admin: Address) {
+        admin::propose_admin(&e, &get_adm(&e).unwrap(), &new_admin);
+        extend(&e);
+    }
+
+    fn accept_admin(e: Env) {
+        let new_adm: Address = admin::accept_admin(&e, &get_adm(&e).unwrap())
+            .unwrap_or_else(|| panic_with_error!(&e, &ContractErrors::NoPendingAdmin));
+        e.storage().instance().set(&StorageKeys::Admin, &new_adm);
+        extend(&e);
+    }
+
+    fn admin(e: Env) -> Option<Address> {
+        extend(&e);
+        get_adm(&e)
+    }
+
+    fn pending_admin(e: Env) -> Option<Address> {
+        extend(&e);
+        admin::pending_admin(&e)
+    }
+
+    fn pause(e: Env) {
+        get_adm(&e).unwrap().require_auth();
+        e.storage().instance().set(&StorageKeys::Paused, &true);
+        extend(&e);
+    }
+
+    fn unpause(e: Env) {
+        get_adm(&e).unwrap().require_auth();
+        e.storage().instance().remove(&StorageKeys::Paused);
+        extend(&e);
+    }
+
+    fn is_paused(e: Env) -> bool {
+        extend(&e);
+        is_paused(&e)
+    }
+
+    fn fee(e: Env) -> Option<(u128, Address)> {
+        extend(&e);
+        get_fee(&e)
+    }
+
     fn set(e: Env, node: BytesN<32>, key: Symbol, value: Value) {
+        if is_paused(&e) {
+            panic_with_error!(&e, &ContractErrors::ContractPaused);
+        }
+
         let domain: Domain = fetch_domain(&e, &node);
         domain.owner.require_auth();
         pay_fee(&e, &domain.owner);
@@ -85,6 +154,7 @@ impl KeyValueDBTrait for KeyValueDB {
         }
     }
 
+    // Removing a key is allowed while the contract is paused
     fn remove(e: Env, node: BytesN<32>, key: Symbol) {
         let domain: Domain = fetch_domain(&e, &node);
         domain.owner.require_auth();
@@ -7,4 +7,7 @@ pub enum ContractErrors {
     FailedToGetRecord = 1,
     FeePaymentFailed = 2,
     KeyWasInvalidated = 3,
+    NoPendingAdmin = 4,
+    InvalidAdmin = 5,
+    ContractPaused = 6,
 }
@@ -1,11 +1,15 @@
 #![cfg(test)]
 
-use soroban_sdk::Env;
+use crate::types::StorageKeys;
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{Address, Env};
+use test_utils::key_value_db_contract::ContractErrors;
 use test_utils::{create_env, create_global_test_data, key_value_db, registry, GlobalTestData};
 
 #[test]
 fn test_keys() {
     let e: Env = create_env();
+    e.budget().reset_unlimited();
 
     let global_test_data: GlobalTestData = create_global_test_data(&e);
     let registry_test_data: registry::TestData = registry::create_test_data(&e);
@@ -27,6 +31,13 @@ fn test_keys() {
         &registry_test_data,
         &key_value_db_test_data,
     );
+    assert_eq!(
+        key_value_db_test_data.contract_client.fee(),
+        Some((
+            key_value_db_test_data.fee,
+            global_test_data.gov_asset.clone()
+        ))
+    );
 
     registry_test_data
         .contract_client
@@ -56,4 +67,143 @@ fn test_keys() {
     assert_eq!(saved_value, key_value_db_test_data.test_value);
 }
 
+#[test]
+fn test_admin_handover() {
+    let e: Env = create_env();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+
+    let global_test_data: GlobalTestData = create_global_test_data(&e);
+    let registry_test_data: registry::TestData = registry::create_test_data(&e);
+    let key_value_db_test_data: key_value_db::TestData = key_value_db::create_test_data(&e);
+
+    registry::init_contract(&global_test_data, &registry_test_data);
+    key_value_db::init_contract(
+        &global_test_data,
+        &registry_test_data,
+        &key_value_db_test_data,
+    );
+
+    let new_adm: Address = Address::generate(&e);
+
+    // The admin can't be replaced with `set_config`
+    assert_eq!(
+        key_value_db_test_data
+            .contract_client
+            .try_set_config(
+                &new_adm,
+                &registry_test_data.contract_client.address,
+                &key_value_db_test_data.fee,
+                &global_test_data.gov_asset,
+                &global_test_data.fee_taker,
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidAdmin.into()
+    );
+
+    assert_eq!(
+        key_value_db_test_data
+            .contract_client
+            .try_accept_admin()
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NoPendingAdmin.into()
+    );
+
+    key_value_db_test_data
+        .contract_client
+        .propose_admin(&new_adm);
+    assert_eq!(e.auths().first().unwrap().0, global_test_data.adm.clone());
+    assert_eq!(
+        key_value_db_test_data.contract_client.pending_admin(),
+        Some(new_adm.clone())
+    );
+
+    key_value_db_test_data.contract_client.accept_admin();
+    assert_eq!(e.auths().first().unwrap().0, new_adm.clone());
+    assert_eq!(
+        key_value_db_test_data.contract_client.admin(),
+        Some(new_adm.clone())
+    );
+    assert_eq!(key_value_db_test_data.contract_client.pending_admin(), None);
+}
+
+#[test]
+fn test_pause() {
+    let e: Env = create_env();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+
+    let global_test_data: GlobalTestData = create_global_test_data(&e);
+    let registry_test_data: registry::TestData = registry::create_test_data(&e);
+    let key_value_db_test_data: key_value_db::TestData = key_value_db::create_test_data(&e);
+
+    global_test_data
+        .col_asset_stellar
+        .mint(&registry_test_data.test_domain_owner, &i128::MAX);
+    global_test_data
+        .gov_asset_stellar
+        .mint(&registry_test_data.test_domain_owner, &i128::MAX);
+
+    registry::init_contract(&global_test_data, &registry_test_data);
+    key_value_db::init_contract(
+        &global_test_data,
+        &registry_test_data,
+        &key_value_db_test_data,
+    );
+
+    registry_test_data.contract_client.set_record(
+        &registry_test_data.test_domain,
+        &registry_test_data.test_tld,
+        &registry_test_data.test_domain_owner,
+        &registry_test_data.test_domain_owner,
+        &registry_test_data.min_duration,
+    );
+
+    key_value_db_test_data.contract_client.pause();
+    assert_eq!(e.auths().first().unwrap().0, global_test_data.adm.clone());
+    assert!(key_value_db_test_data.contract_client.is_paused());
+
+    assert_eq!(
+        key_value_db_test_data
+            .contract_client
+            .try_set(
+                &registry_test_data.test_node,
+                &key_value_db_test_data.test_key,
+                &key_value_db_test_data.test_value,
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::ContractPaused.into()
+    );
+
+    key_value_db_test_data.contract_client.unpause();
+    key_value_db_test_data.contract_client.set(
+        &registry_test_data.test_node,
+        &key_value_db_test_data.test_key,
+        &key_value_db_test_data.test_value,
+    );
+    assert!(key_value_db_test_data
+        .contract_client
+        .get(
+            &registry_test_data.test_node,
+            &key_value_db_test_data.test_key,
+        )
+        .is_some());
+
+    // Owners can still remove their keys while the contract is paused
+    key_value_db_test_data.contract_client.pause();
+    key_value_db_test_data.contract_client.remove(
+        &registry_test_data.test_node,
+        &key_value_db_test_data.test_key,
+    );
+    e.as_contract(&key_value_db_test_data.contract_client.address, || {
+        assert!(!e.storage().persistent().has(&StorageKeys::Key((
+            registry_test_data.test_node.clone(),
+            key_value_db_test_data.test_key.clone(),
+        ))));
+    });
+}
+
 // TODO: test cases where no signature is provided, snapshot changed, providing wrong Record node and not enough funds to pay fee
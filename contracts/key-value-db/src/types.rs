@@ -8,6 +8,7 @@ pub enum StorageKeys {
     Fee,
     Currency,
     Treasury,
+    Paused,
 
     // Persistent keys
     Key((BytesN<32>, Symbol)), // -> Returns (Value, u64) where the u64 value is the domain snapshot
@@ -31,10 +31,15 @@ pub fn remove_key(e: &Env, node: &BytesN<32>, key: &Symbol) {
         .remove(&StorageKeys::Key((node.clone(), key.clone())));
 }
 
+pub fn get_fee(e: &Env) -> Option<(u128, Address)> {
+    let fee: u128 = e.storage().instance().get(&StorageKeys::Fee)?;
+    let currency: Address = e.storage().instance().get(&StorageKeys::Currency)?;
+    Some((fee, currency))
+}
+
 pub fn pay_fee(e: &Env, caller: &Address) {
     let treasury: Address = e.storage().instance().get(&StorageKeys::Treasury).unwrap();
-    let currency: Address = e.storage().instance().get(&StorageKeys::Currency).unwrap();
-    let fee: u128 = e.storage().instance().get(&StorageKeys::Fee).unwrap();
+    let (fee, currency) = get_fee(&e).unwrap();
 
     let result = token::Client::new(&e, &currency).try_transfer(&caller, &treasury, &(fee as i128));
 
@@ -47,6 +52,10 @@ pub fn get_adm(e: &Env) -> Option<Address> {
     e.storage().instance().get(&StorageKeys::Admin)
 }
 
+pub fn is_paused(e: &Env) -> bool {
+    e.storage().instance().has(&StorageKeys::Paused)
+}
+
 pub fn fetch_domain(e: &Env, node: &BytesN<32>) -> Domain {
     let registry = e.storage().instance().get(&StorageKeys::Registry).unwrap();
     let result = e.try_invoke_contract::<Option<Record>, RegistryErrors>(
@@ -1,13 +1,51 @@
 use crate::errors::ContractErrors;
-use crate::events::emit_offer_accepted;
-use crate::storage::core::{CoreData, CoreDataEntity, OffersConfig};
+use crate::events::{
+    emit_address_updated, emit_approval_revoked, emit_approval_set, emit_blocked_name_updated,
+    emit_buy_offer_burned, emit_collateral_config_updated, emit_collateral_rebalanced,
+    emit_commitment_set, emit_commitments_config_updated, emit_duration_config_updated,
+    emit_expiration_config_updated, emit_initialized, emit_liquidation_config_updated,
+    emit_nft_config_updated, emit_offer_accepted, emit_offers_config_updated,
+    emit_operator_updated, emit_oracle_config_updated, emit_oracle_updated, emit_paused,
+    emit_premium_price_updated, emit_pricing_table_updated, emit_record_burned,
+    emit_record_renewed, emit_role_granted, emit_role_revoked, emit_sale_offer_burned,
+    emit_sub_record_revoked, emit_sub_record_set, emit_sub_record_transferred,
+    emit_subdomains_config_updated, emit_tld_created, emit_tld_status_updated, emit_tld_updated,
+    emit_unpaused, emit_upgraded,
+};
+use crate::storage::approvals::{Approval, ApprovalsEntity};
+use crate::storage::commitments::CommitmentsEntity;
+use crate::storage::core::{
+    CollateralConfig, CommitmentsConfig, CoreData, CoreDataEntity, DurationConfig,
+    ExpirationConfig, LiquidationConfig, NftConfig, OffersConfig, OracleConfig, PauseScope,
+    SubdomainsConfig, MAX_GRACE_PERIOD,
+};
 use crate::storage::offers::{Offer, OffersDataKeys, OffersFunc};
+use crate::storage::owners::{OwnersEntity, MAX_PAGE_SIZE};
+use crate::storage::pricing::{PricingEntity, PricingTable};
 use crate::storage::record::{Domain, Record, RecordEntity, RecordKeys, SubDomain};
-use crate::utils::offers::{set_new_buy_offer, set_sale_offer, update_buy_offer};
-use crate::utils::records::{generate_node, record_price, validate_domain};
-use num_integer::div_ceil;
+use crate::storage::roles::{Role, RolesEntity};
+use crate::storage::subdomains::SubdomainsEntity;
+use crate::storage::tlds::{NameRules, TldConfig, TldStatus, TldsEntity};
+use crate::utils::nft::{
+    token_domain, token_metadata, token_uri, TokenMetadata, MAX_BASE_URI_LENGTH,
+};
+use crate::utils::offers::{
+    burn_domain_offers, offer_fee, set_new_buy_offer, set_sale_offer, update_buy_offer,
+};
+use crate::utils::quotes::{
+    offer_quotes, rebalance_quote, registration_quote, renewal_quote, OfferQuote, RebalanceQuote,
+    RegistrationQuote, RenewalQuote,
+};
+use crate::utils::records::{
+    active_approval, burn_sub, burn_subdomains, can_transfer, commitment_hash, generate_node,
+    is_claimable, liquidate_domain, missing_collateral, parent_controller, pay_duration_fee,
+    register_domain, resolve_name, sub_controller, transfer_domain, validate_domain, validate_sub,
+    MAX_BURNED_SUBDOMAINS,
+};
+use crate::utils::tlds::{domain_tld_status, transfer_protocol_fee, validate_tld_config};
+use common::admin;
 use soroban_sdk::{
-    contract, contractimpl, panic_with_error, token, Address, Bytes, BytesN, Env, Vec,
+    contract, contractimpl, panic_with_error, token, Address, Bytes, BytesN, Env, String, Vec,
 };
 
 pub trait RegistryContractTrait {
@@ -20,11 +58,91 @@ pub trait RegistryContractTrait {
         allowed_tlds: Vec<Bytes>,
     );
 
-    fn set_offers_config(e: Env, fee_taker: Address, fee: u128);
-    fn set_oracle(e: Env, oracle: Address);
-    fn upgrade(e: Env, new_wasm_hash: BytesN<32>);
-    fn update_tlds(e: Env, tlds: Vec<Bytes>);
-
+    // The `caller` needs to be the admin or an address with the role the function requires:
+    // - `set_offers_config`, `set_expiration_config` and `set_duration_config`: FeeManager
+    // - `set_oracle` and `set_collateral_config`: PricingManager
+    // - `upgrade`: Upgrader
+    // - `create_tld`, `update_tld`, `set_tld_status` and `set_blocked_name`: TldManager
+    fn set_offers_config(e: Env, caller: Address, fee_taker: Address, fee: u128);
+    fn set_oracle(e: Env, caller: Address, oracle: Address);
+    fn upgrade(e: Env, caller: Address, new_wasm_hash: BytesN<32>);
+
+    // Each TLD has its own config: an operator, if the registration is public or only the operator can register
+    // domains, the min duration, the pricing table, the name rules and the share of the protocol fees that goes to
+    // the operator.
+    fn create_tld(e: Env, caller: Address, tld: Bytes, config: TldConfig);
+    fn update_tld(e: Env, caller: Address, tld: Bytes, config: TldConfig);
+    fn tld_config(e: Env, tld: Bytes) -> Option<TldConfig>;
+
+    // The status of a TLD defines what happens to its domains (see `TldStatus`), only active TLDs are in the allowed
+    // TLDs list. A sunset date can't be in the past.
+    fn set_tld_status(e: Env, caller: Address, tld: Bytes, status: TldStatus);
+    fn tld_status(e: Env, tld: Bytes) -> TldStatus;
+
+    // Blocked names can't be registered, domains that already exist are not affected
+    fn set_blocked_name(e: Env, caller: Address, domain: Bytes, tld: Bytes, blocked: bool);
+    fn is_blocked_name(e: Env, domain: Bytes, tld: Bytes) -> bool;
+
+    // Addresses with the Pauser role can pause a scope of the contract but only the admin can unpause it
+    fn pause(e: Env, caller: Address, scope: PauseScope);
+    fn unpause(e: Env, scope: PauseScope);
+    fn is_paused(e: Env, scope: PauseScope) -> bool;
+
+    // Pricing tables define the USD price of the domains based on their length, TLDs can have their own table and
+    // specific domains can have a premium price. They require the PricingManager role.
+    // A `None` TLD updates the default table and a `None` table removes the table of the TLD (or resets the default one)
+    fn set_pricing_table(e: Env, caller: Address, tld: Option<Bytes>, table: Option<PricingTable>);
+
+    // Rules used to accept the oracle price (max age, TWAP, fallback oracle and bounds), it requires the PricingManager role
+    fn set_oracle_config(e: Env, caller: Address, config: OracleConfig);
+    fn oracle_config(e: Env) -> OracleConfig;
+    fn pricing_table(e: Env, tld: Option<Bytes>) -> PricingTable;
+    fn set_premium_price(e: Env, caller: Address, domain: Bytes, tld: Bytes, price: Option<u128>);
+    fn premium_price(e: Env, domain: Bytes, tld: Bytes) -> Option<u128>;
+
+    // Only the admin can grant and revoke roles
+    fn grant_role(e: Env, role: Role, account: Address);
+    fn revoke_role(e: Env, role: Role, account: Address);
+    fn has_role(e: Env, role: Role, account: Address) -> bool;
+
+    // The admin is transferred in two steps: the current admin proposes a new admin and the new admin accepts it
+    fn propose_admin(e: Env, new_admin: Address);
+    fn accept_admin(e: Env);
+    fn admin(e: Env) -> Address;
+    fn pending_admin(e: Env) -> Option<Address>;
+
+    // The grace period is the time after the expiration date where only the owner can renew the domain, once is over
+    // anyone can register it again and the old collateral is released following the `protocol_share`.
+    // The grace period can't be longer than `MAX_GRACE_PERIOD`.
+    fn set_expiration_config(e: Env, caller: Address, grace_period: u64, protocol_share: u128);
+
+    // The keeper reward is the share of the collateral paid to whoever liquidates an expired domain, it requires the
+    // FeeManager role
+    fn set_liquidation_config(e: Env, caller: Address, config: LiquidationConfig);
+    fn liquidation_config(e: Env) -> LiquidationConfig;
+
+    // Domains whose collateral falls below the `min_ratio` of the current collateral price can't be transferred until
+    // they are rebalanced, taking an offer tops them up with the amount of the offer. It requires the PricingManager
+    // role
+    fn set_collateral_config(e: Env, caller: Address, config: CollateralConfig);
+    fn collateral_config(e: Env) -> CollateralConfig;
+
+    // The max duration limits how far in the future the expiration date of a domain can be and the annual fee is a share
+    // of the collateral charged for every year of registration (or renewal), it goes to the `fee_taker`
+    fn set_duration_config(e: Env, caller: Address, config: DurationConfig);
+    fn duration_config(e: Env) -> DurationConfig;
+
+    // The max depth limits how many levels of subdomains can be created (for example `a.b.example.xlm` is 2 levels)
+    // and `parent_can_revoke` defines if the controller of a parent can take back a subdomain with its own owner
+    fn set_subdomains_config(e: Env, max_depth: u32, parent_can_revoke: bool);
+
+    // Registrations can use a commitment so the name can't be front-run: the user commits the hash of the name (see
+    // `commitment_hash`) and after `min_delay` ledgers reveals it with `reveal_and_register`.
+    // If `required` is true, `set_record` is disabled and every registration needs a commitment.
+    fn set_commitments_config(e: Env, caller: Address, config: CommitmentsConfig);
+    fn commitments_config(e: Env) -> CommitmentsConfig;
+
+    // Can fail with `CommitmentRequired` if the commitments config requires a commitment
     fn set_record(
         e: Env,
         domain: Bytes,
@@ -34,22 +152,163 @@ pub trait RegistryContractTrait {
         duration: u64,
     );
 
+    // Saves a commitment, it can be revealed between `min_delay` and `max_age` ledgers after this call.
+    // A commitment can't be made again until its `max_age` is over.
+    fn commit(e: Env, commitment: BytesN<32>);
+
+    // Registers the domain of a commitment, the commitment is removed once it's used
+    fn reveal_and_register(
+        e: Env,
+        domain: Bytes,
+        tld: Bytes,
+        owner: Address,
+        address: Address,
+        duration: u64,
+        secret: BytesN<32>,
+    );
+
+    // The hash of a registration, clients should calculate it locally (the keccak256 of the xdr of
+    // `(domain, tld, owner, secret)`) so the name is not sent to the network before the reveal
+    fn commitment_hash(
+        e: Env,
+        domain: Bytes,
+        tld: Bytes,
+        owner: Address,
+        secret: BytesN<32>,
+    ) -> BytesN<32>;
+
+    // Extends the expiration date of a domain, it can be paid by any address (not only the owner)
+    // If the domain is expired, only the owner can renew it and only during the grace period
+    // The collateral is priced again with the current oracle price: if it's higher the caller pays the difference and
+    // if it's lower the surplus is returned to the owner of the domain.
+    // The snapshot is not updated so subdomains and offers are still valid after the renewal.
+    fn renew_record(e: Env, caller: Address, key: RecordKeys, duration: u64);
+
+    // Prices the collateral of a domain again with the current oracle price without changing its expiration date.
+    // If the domain has more collateral than it needs, the owner can withdraw the surplus (only the owner can call it in
+    // that case). If it has less, any address can pay the difference.
+    // Expired domains can't be rebalanced and domains without a saved name fail with `UnpricedDomain`.
+    fn rebalance_collateral(e: Env, caller: Address, node: BytesN<32>);
+
     fn update_address(e: Env, key: RecordKeys, address: Address);
 
+    // The parent can be either a domain or another subdomain, the controller of the parent (its owner or, if it doesn't
+    // have one, the controller of its own parent) is the one who needs to sign the transaction.
+    // Subdomains take the snapshot of the root domain.
+    // A subdomain with its own owner can't be replaced, the parent needs to revoke it first.
     fn set_sub(e: Env, sub: Bytes, parent: RecordKeys, address: Address);
 
+    // The controller of a subdomain can give it to a different address, the new owner can update its address and create
+    // its own subdomains without the signature of the parent.
+    // Unlike domains, the subdomains below it are not invalidated because they use the snapshot of the root domain.
+    fn transfer_sub(e: Env, node: BytesN<32>, to: Address);
+
+    fn update_sub_address(e: Env, node: BytesN<32>, address: Address);
+
+    // If it's allowed by the subdomains config, the controller of the parent can remove the owner of a subdomain
+    fn revoke_sub(e: Env, node: BytesN<32>);
+
+    // Returns the owner of a subdomain, `None` means the subdomain is managed by the controller of its parent
+    fn sub_owner(e: Env, node: BytesN<32>) -> Option<Address>;
+
+    // Lists the direct subdomains of a parent (a domain or another subdomain) starting from the `cursor` position, a
+    // page checks `MAX_PAGE_SIZE` positions at most.
+    // Outdated subdomains (created before the root domain changed its snapshot) are skipped, so a page can have fewer
+    // subdomains than the limit and clients should move the cursor by the limit instead of the length of the page.
+    fn subdomains_of(e: Env, parent: BytesN<32>, cursor: u32, limit: u32) -> Vec<BytesN<32>>;
+
     // Get a record based on the node hash
     fn record(e: Env, key: RecordKeys) -> Option<Record>;
 
+    // Resolves a full name like `a.b.example.xlm` to the address of its record. It returns `None` if the name is invalid
+    // or if the record doesn't exist, is expired or is outdated.
+    fn resolve(e: Env, name: Bytes) -> Option<Address>;
+
+    // Same as `resolve` but it returns the whole record, or the reason why the name can't be resolved
+    fn resolve_record(e: Env, name: Bytes) -> Result<Record, ContractErrors>;
+
     fn parse_domain(e: Env, domain: Bytes, tld: Bytes) -> BytesN<32>;
 
-    // The owner of a domain can transfer it to a different address
+    // Read only quotes so wallets can show what a registration, a renewal or an offer is going to cost before sending
+    // the transaction. They fail with the same errors as `set_record`, `renew_record` and `take_offer` would (except for
+    // the auth and the existence checks of the records).
+    fn quote_registration(e: Env, domain: Bytes, tld: Bytes, duration: u64) -> RegistrationQuote;
+    fn quote_renewal(e: Env, key: RecordKeys, duration: u64) -> RenewalQuote;
+    // Quotes the sale and the buy offers of a domain, it's empty if the domain doesn't have offers
+    fn quote_offer(e: Env, node: BytesN<32>) -> Vec<OfferQuote>;
+    fn quote_rebalance(e: Env, node: BytesN<32>) -> RebalanceQuote;
+
+    // The owner of a domain can transfer it to a different address, `from` needs to be the owner of the domain
+    // Domains past their grace period can't be transferred, approved, offered or sold (`ExpiredDomain`)
     // This method also invalidates all the subdomains, this is just for prevention purposes but this can be changed in the future if people think there is no risk on it.
-    fn transfer(e: Env, key: RecordKeys, to: Address);
+    fn transfer(e: Env, from: Address, to: Address, token_id: BytesN<32>);
+
+    // The owner (or an operator of the owner) can approve an address to transfer a domain until the ledger
+    // `live_until_ledger` (included). A domain has one approval at most and it's cleared once the domain changes its
+    // snapshot (for example after a transfer or a sale).
+    fn approve(
+        e: Env,
+        approver: Address,
+        approved: Address,
+        token_id: BytesN<32>,
+        live_until_ledger: u32,
+    );
+    fn revoke_approval(e: Env, caller: Address, node: BytesN<32>);
+    fn approval(e: Env, node: BytesN<32>) -> Option<Approval>;
+
+    // Operators can transfer and approve all the domains of an owner, including the domains the owner gets later
+    fn set_approval_for_all(e: Env, owner: Address, operator: Address, approved: bool);
+    fn is_approved_for_all(e: Env, owner: Address, operator: Address) -> bool;
+
+    // Same as `transfer` but it can be signed by the owner, the approved address of the domain or an operator
+    fn transfer_from(e: Env, spender: Address, from: Address, to: Address, token_id: BytesN<32>);
+
+    // Once the grace period of an expired domain is over, anyone can liquidate it: the domain, its subdomains and its
+    // offers are removed, the `caller` gets the keeper reward, the `fee_taker` gets the protocol share of the
+    // expiration config and the rest of the collateral goes back to the owner.
+    // Fails with `NotLiquidatable` if the domain is not expired or if it's still in the grace period.
+    fn liquidate(e: Env, caller: Address, node: BytesN<32>);
 
     // When burning a record, the record gets removed from the storage and the collateral is released
+    // Burning a record also burns its subdomains (`MAX_BURNED_SUBDOMAINS` at most, the rest can be removed with
+    // `burn_orphan_subdomains`), and burning a domain cancels its offers (the amount of a buy offer goes back to the
+    // buyer). Domains past their grace period can't be burned, they are released with `liquidate`
     fn burn_record(e: Env, key: RecordKeys);
 
+    // Anyone can burn the subdomains left in the index of a parent that was burned or liquidated (or a domain past its
+    // grace period), with a max of `MAX_BURNED_SUBDOMAINS` per call. It returns the amount of subdomains the parent still
+    // has in the index (zero once it's clean) and it fails with `InvalidParent` if the parent still exists.
+    // Registering a domain again burns its leftover subdomains, it fails with `PendingSubdomains` if there are more than
+    // `MAX_BURNED_SUBDOMAINS` so they have to be burned here first.
+    fn burn_orphan_subdomains(e: Env, parent: BytesN<32>) -> u32;
+
+    // Domains follow the usual NFT interface so wallets and marketplaces can show them like any other collectible: the
+    // token id is the node of the domain, transfers use `transfer`/`transfer_from` and approvals use `approve` and
+    // `set_approval_for_all`. Subdomains are not tokens.
+    // The name, symbol and base URI of the collection require the TldManager role, the base URI can't be longer than
+    // `MAX_BASE_URI_LENGTH`.
+    fn set_nft_config(e: Env, caller: Address, config: NftConfig);
+    fn name(e: Env) -> String;
+    fn symbol(e: Env) -> String;
+
+    // The amount of domains owned by an address, domains that haven't changed their owner since before we started
+    // indexing the owners are not included
+    fn balance(e: Env, owner: Address) -> u32;
+
+    // They fail with `RecordDoesntExist` if the domain doesn't exist
+    fn owner_of(e: Env, node: BytesN<32>) -> Address;
+    fn get_approved(e: Env, node: BytesN<32>) -> Option<Address>;
+    fn token_uri(e: Env, node: BytesN<32>) -> Option<String>;
+    fn token_metadata(e: Env, node: BytesN<32>) -> TokenMetadata;
+
+    // Lists the domains of an owner starting from the `cursor` position, a page has `MAX_PAGE_SIZE` domains at most.
+    // The order of the list changes when a domain leaves it (the last domain takes its position), so clients should
+    // not rely on the positions between transactions.
+    fn domains_of(e: Env, owner: Address, cursor: u32, limit: u32) -> Vec<BytesN<32>>;
+
+    // Same as `balance`
+    fn domain_count(e: Env, owner: Address) -> u32;
+
     // Users can set a domain for sale or set a buy offer for that domain
     // Users set the offer amount and if is a buy offer the contract takes the amount and keep it in case the owner accepts it
     // If the offer to set is a BuyOffer and there is already one set we check if the amount is higher,
@@ -82,6 +341,7 @@ impl RegistryContractTrait for RegistryContract {
         if let Some(_) = e.core_data() {
             panic_with_error!(&e, &ContractErrors::AlreadyStarted);
         } else {
+            emit_initialized(&e, &adm, &col_asset, &min_duration, &allowed_tlds);
             e.set_core_data(&CoreData {
                 adm,
                 node_rate,
@@ -93,32 +353,345 @@ impl RegistryContractTrait for RegistryContract {
         }
     }
 
-    fn set_offers_config(e: Env, fee_taker: Address, fee: u128) {
+    fn set_offers_config(e: Env, caller: Address, fee_taker: Address, fee: u128) {
         e.bump_core();
-        e.is_adm();
+        e.require_role(&Role::FeeManager, &caller);
+        emit_offers_config_updated(&e, &fee_taker, &fee);
         e.set_offers_config(&OffersConfig { fee_taker, fee });
     }
 
-    fn set_oracle(e: Env, oracle: Address) {
+    fn set_oracle(e: Env, caller: Address, oracle: Address) {
         e.bump_core();
-        e.is_adm();
+        e.require_role(&Role::PricingManager, &caller);
         e.set_oracle(&oracle);
+        emit_oracle_updated(&e, &oracle);
     }
 
-    fn upgrade(e: Env, hash: BytesN<32>) {
+    fn upgrade(e: Env, caller: Address, hash: BytesN<32>) {
         e.bump_core();
-        e.is_adm();
+        e.require_role(&Role::Upgrader, &caller);
+        emit_upgraded(&e, &hash);
         e.deployer().update_current_contract_wasm(hash);
     }
 
-    fn update_tlds(e: Env, tlds: Vec<Bytes>) {
+    fn create_tld(e: Env, caller: Address, tld: Bytes, config: TldConfig) {
+        e.bump_core();
+        e.require_role(&Role::TldManager, &caller);
+
+        if e.tld_config(&tld).is_some() {
+            panic_with_error!(&e, &ContractErrors::TldAlreadyExists);
+        }
+
+        validate_tld_config(&e, &tld, &config);
+
+        let mut core: CoreData = e.core_data().unwrap();
+        core.allowed_tlds.push_back(tld.clone());
+        e.set_core_data(&core);
+        e.set_tld_config(&tld, &config);
+        emit_tld_created(&e, &tld, &config);
+    }
+
+    fn update_tld(e: Env, caller: Address, tld: Bytes, config: TldConfig) {
+        e.bump_core();
+        e.require_role(&Role::TldManager, &caller);
+
+        if e.tld_config(&tld).is_none() {
+            panic_with_error!(&e, &ContractErrors::UnsupportedTLD);
+        }
+
+        validate_tld_config(&e, &tld, &config);
+        e.set_tld_config(&tld, &config);
+        emit_tld_updated(&e, &tld, &config);
+    }
+
+    fn tld_config(e: Env, tld: Bytes) -> Option<TldConfig> {
+        e.bump_core();
+        e.tld_config(&tld)
+    }
+
+    fn set_tld_status(e: Env, caller: Address, tld: Bytes, status: TldStatus) {
+        e.bump_core();
+        e.require_role(&Role::TldManager, &caller);
+
+        let config: TldConfig = e
+            .tld_config(&tld)
+            .unwrap_or_else(|| panic_with_error!(&e, &ContractErrors::UnsupportedTLD));
+
+        if let TldStatus::Sunset(date) = status {
+            if date < e.ledger().timestamp() {
+                panic_with_error!(&e, &ContractErrors::InvalidConfig);
+            }
+        }
+
+        // The config is saved so TLDs from the old allowed list keep their config once they leave the list
+        e.set_tld_config(&tld, &config);
+
+        let mut core: CoreData = e.core_data().unwrap();
+        let index: Option<u32> = core.allowed_tlds.first_index_of(tld.clone());
+        match (&status, index) {
+            (TldStatus::Active, None) => core.allowed_tlds.push_back(tld.clone()),
+            (TldStatus::Active, Some(_)) => {}
+            (_, Some(index)) => {
+                core.allowed_tlds.remove(index);
+            }
+            (_, None) => {}
+        }
+        e.set_core_data(&core);
+
+        e.set_tld_status(&tld, &status);
+        emit_tld_status_updated(&e, &tld, &status);
+    }
+
+    fn tld_status(e: Env, tld: Bytes) -> TldStatus {
+        e.bump_core();
+        e.tld_status(&tld)
+    }
+
+    fn set_blocked_name(e: Env, caller: Address, domain: Bytes, tld: Bytes, blocked: bool) {
+        e.bump_core();
+        e.require_role(&Role::TldManager, &caller);
+
+        let node: BytesN<32> = generate_node(&e, &domain, &tld);
+        e.set_blocked(&node, blocked);
+        emit_blocked_name_updated(&e, &node, &blocked);
+    }
+
+    fn is_blocked_name(e: Env, domain: Bytes, tld: Bytes) -> bool {
+        e.bump_core();
+        e.is_blocked(&generate_node(&e, &domain, &tld))
+    }
+
+    fn pause(e: Env, caller: Address, scope: PauseScope) {
+        e.bump_core();
+        e.require_role(&Role::Pauser, &caller);
+        e.set_paused(&scope, true);
+        emit_paused(&e, &scope);
+    }
+
+    fn unpause(e: Env, scope: PauseScope) {
         e.bump_core();
         e.is_adm();
+        e.set_paused(&scope, false);
+        emit_unpaused(&e, &scope);
+    }
+
+    fn is_paused(e: Env, scope: PauseScope) -> bool {
+        e.bump_core();
+        e.is_paused(&scope)
+    }
+
+    fn set_pricing_table(e: Env, caller: Address, tld: Option<Bytes>, table: Option<PricingTable>) {
+        e.bump_core();
+        e.require_role(&Role::PricingManager, &caller);
+
+        if let Some(table) = &table {
+            if table.length_prices.is_empty() || table.length_prices.contains(0) {
+                panic_with_error!(&e, &ContractErrors::InvalidConfig);
+            }
+        }
+
+        // The table of a TLD is saved in its config
+        match &tld {
+            Some(tld) => {
+                let mut config: TldConfig = e
+                    .tld_config(tld)
+                    .unwrap_or_else(|| panic_with_error!(&e, &ContractErrors::UnsupportedTLD));
+                config.pricing_table = table.clone().unwrap_or(PricingTable {
+                    length_prices: Vec::new(&e),
+                });
+                e.set_tld_config(tld, &config);
+            }
+            None => e.set_default_pricing_table(&table),
+        }
+
+        emit_pricing_table_updated(&e, &tld, &table);
+    }
+
+    fn set_oracle_config(e: Env, caller: Address, config: OracleConfig) {
+        e.bump_core();
+        e.require_role(&Role::PricingManager, &caller);
+
+        // The TWAP is limited to bound the amount of calls to the oracle
+        if config.twap_records > 12 || (config.max_price > 0 && config.min_price > config.max_price)
+        {
+            panic_with_error!(&e, &ContractErrors::InvalidConfig);
+        }
+
+        e.set_oracle_config(&config);
+        emit_oracle_config_updated(&e, &config);
+    }
+
+    fn oracle_config(e: Env) -> OracleConfig {
+        e.bump_core();
+        e.oracle_config()
+    }
+
+    fn pricing_table(e: Env, tld: Option<Bytes>) -> PricingTable {
+        e.bump_core();
+        e.pricing_table(&tld)
+    }
+
+    fn set_premium_price(e: Env, caller: Address, domain: Bytes, tld: Bytes, price: Option<u128>) {
+        e.bump_core();
+        e.require_role(&Role::PricingManager, &caller);
+
+        if price == Some(0) {
+            panic_with_error!(&e, &ContractErrors::InvalidConfig);
+        }
+
+        let node: BytesN<32> = generate_node(&e, &domain, &tld);
+        e.set_premium_price(&node, &price);
+        emit_premium_price_updated(&e, &node, &price);
+    }
+
+    fn premium_price(e: Env, domain: Bytes, tld: Bytes) -> Option<u128> {
+        e.bump_core();
+        e.premium_price(&generate_node(&e, &domain, &tld))
+    }
+
+    fn grant_role(e: Env, role: Role, account: Address) {
+        e.bump_core();
+        e.is_adm();
+        e.grant_role(&role, &account);
+        emit_role_granted(&e, &role, &account);
+    }
+
+    fn revoke_role(e: Env, role: Role, account: Address) {
+        e.bump_core();
+        e.is_adm();
+        e.revoke_role(&role, &account);
+        emit_role_revoked(&e, &role, &account);
+    }
+
+    fn has_role(e: Env, role: Role, account: Address) -> bool {
+        e.bump_core();
+        e.has_role(&role, &account)
+    }
+
+    fn propose_admin(e: Env, new_admin: Address) {
+        e.bump_core();
+        admin::propose_admin(&e, &e.core_data().unwrap().adm, &new_admin);
+    }
+
+    fn accept_admin(e: Env) {
+        e.bump_core();
         let mut core: CoreData = e.core_data().unwrap();
-        core.allowed_tlds = tlds;
+        core.adm = admin::accept_admin(&e, &core.adm)
+            .unwrap_or_else(|| panic_with_error!(&e, &ContractErrors::NoPendingAdmin));
         e.set_core_data(&core);
     }
 
+    fn admin(e: Env) -> Address {
+        e.bump_core();
+        e.core_data().unwrap().adm
+    }
+
+    fn pending_admin(e: Env) -> Option<Address> {
+        e.bump_core();
+        admin::pending_admin(&e)
+    }
+
+    fn set_expiration_config(e: Env, caller: Address, grace_period: u64, protocol_share: u128) {
+        e.bump_core();
+        e.require_role(&Role::FeeManager, &caller);
+
+        if protocol_share > 100_0000000 || grace_period > MAX_GRACE_PERIOD {
+            panic_with_error!(&e, &ContractErrors::InvalidConfig);
+        }
+
+        e.set_expiration_config(&ExpirationConfig {
+            grace_period,
+            protocol_share,
+        });
+        emit_expiration_config_updated(&e, &grace_period, &protocol_share);
+    }
+
+    fn set_liquidation_config(e: Env, caller: Address, config: LiquidationConfig) {
+        e.bump_core();
+        e.require_role(&Role::FeeManager, &caller);
+
+        if config.keeper_reward > 100_0000000 {
+            panic_with_error!(&e, &ContractErrors::InvalidConfig);
+        }
+
+        e.set_liquidation_config(&config);
+        emit_liquidation_config_updated(&e, &config);
+    }
+
+    fn liquidation_config(e: Env) -> LiquidationConfig {
+        e.bump_core();
+        e.liquidation_config()
+    }
+
+    fn set_collateral_config(e: Env, caller: Address, config: CollateralConfig) {
+        e.bump_core();
+        e.require_role(&Role::PricingManager, &caller);
+
+        if config.min_ratio > 100_0000000 {
+            panic_with_error!(&e, &ContractErrors::InvalidConfig);
+        }
+
+        e.set_collateral_config(&config);
+        emit_collateral_config_updated(&e, &config);
+    }
+
+    fn collateral_config(e: Env) -> CollateralConfig {
+        e.bump_core();
+        e.collateral_config()
+    }
+
+    fn set_duration_config(e: Env, caller: Address, config: DurationConfig) {
+        e.bump_core();
+        e.require_role(&Role::FeeManager, &caller);
+
+        let core_data: CoreData = e.core_data().unwrap();
+        if (config.max_duration > 0 && config.max_duration < core_data.min_duration)
+            || config.annual_fee > 100_0000000
+        {
+            panic_with_error!(&e, &ContractErrors::InvalidConfig);
+        }
+
+        e.set_duration_config(&config);
+        emit_duration_config_updated(&e, &config);
+    }
+
+    fn duration_config(e: Env) -> DurationConfig {
+        e.bump_core();
+        e.duration_config()
+    }
+
+    fn set_subdomains_config(e: Env, max_depth: u32, parent_can_revoke: bool) {
+        e.bump_core();
+        e.is_adm();
+
+        if max_depth == 0 {
+            panic_with_error!(&e, &ContractErrors::InvalidConfig);
+        }
+
+        e.set_subdomains_config(&SubdomainsConfig {
+            max_depth,
+            parent_can_revoke,
+        });
+        emit_subdomains_config_updated(&e, &max_depth, &parent_can_revoke);
+    }
+
+    fn set_commitments_config(e: Env, caller: Address, config: CommitmentsConfig) {
+        e.bump_core();
+        e.require_role(&Role::TldManager, &caller);
+
+        if config.min_delay == 0 || config.max_age <= config.min_delay {
+            panic_with_error!(&e, &ContractErrors::InvalidConfig);
+        }
+
+        e.set_commitments_config(&config);
+        emit_commitments_config_updated(&e, &config);
+    }
+
+    fn commitments_config(e: Env) -> CommitmentsConfig {
+        e.bump_core();
+        e.commitments_config()
+    }
+
     fn set_record(
         e: Env,
         domain: Bytes,
@@ -128,53 +701,187 @@ impl RegistryContractTrait for RegistryContract {
         duration: u64,
     ) {
         e.bump_core();
+        e.require_not_paused(&PauseScope::Registration);
         owner.require_auth();
 
-        validate_domain(&e, &domain);
+        if e.commitments_config().required {
+            panic_with_error!(&e, &ContractErrors::CommitmentRequired);
+        }
 
-        let core_data: CoreData = e.core_data().unwrap();
+        register_domain(&e, domain, tld, owner, address, duration);
+    }
 
-        if !core_data.allowed_tlds.contains(tld.clone()) {
-            panic_with_error!(&e, &ContractErrors::UnsupportedTLD);
+    fn commit(e: Env, commitment: BytesN<32>) {
+        e.bump_core();
+        e.require_not_paused(&PauseScope::Registration);
+
+        let config: CommitmentsConfig = e.commitments_config();
+
+        // A live commitment can't be made again, otherwise anyone could delay its reveal
+        if let Some(ledger) = e.commitment(&commitment) {
+            if e.ledger().sequence() <= ledger + config.max_age {
+                panic_with_error!(&e, &ContractErrors::CommitmentAlreadyExists);
+            }
         }
 
-        let node_hash: BytesN<32> = generate_node(&e, &domain, &tld);
-        let record_key: RecordKeys = RecordKeys::Record(node_hash.clone());
+        e.set_commitment(&commitment, config.max_age);
+        emit_commitment_set(&e, &commitment);
+    }
 
-        // We check if the record already exists, if it does then we panic
-        if e.record(&record_key).is_some() {
-            panic_with_error!(&e, &ContractErrors::RecordAlreadyExist);
+    fn reveal_and_register(
+        e: Env,
+        domain: Bytes,
+        tld: Bytes,
+        owner: Address,
+        address: Address,
+        duration: u64,
+        secret: BytesN<32>,
+    ) {
+        e.bump_core();
+        e.require_not_paused(&PauseScope::Registration);
+        owner.require_auth();
+
+        let config: CommitmentsConfig = e.commitments_config();
+        let commitment: BytesN<32> = commitment_hash(&e, &domain, &tld, &owner, &secret);
+        let ledger: u32 = e
+            .commitment(&commitment)
+            .unwrap_or_else(|| panic_with_error!(&e, &ContractErrors::CommitmentNotFound));
+
+        if e.ledger().sequence() < ledger + config.min_delay {
+            panic_with_error!(&e, &ContractErrors::CommitmentTooRecent);
         }
 
-        if duration < core_data.min_duration {
-            panic_with_error!(&e, &ContractErrors::InvalidDuration);
+        if e.ledger().sequence() > ledger + config.max_age {
+            panic_with_error!(&e, &ContractErrors::CommitmentExpired);
         }
 
-        let exp_date: u64 = e.ledger().timestamp() + duration;
-        let (_, collateral) = record_price(&e, &e.oracle(), domain.len());
+        e.remove_commitment(&commitment);
+        register_domain(&e, domain, tld, owner, address, duration);
+    }
 
-        token::Client::new(&e, &core_data.col_asset).transfer(
-            &owner,
-            &e.current_contract_address(),
-            &(collateral as i128),
+    fn commitment_hash(
+        e: Env,
+        domain: Bytes,
+        tld: Bytes,
+        owner: Address,
+        secret: BytesN<32>,
+    ) -> BytesN<32> {
+        commitment_hash(&e, &domain, &tld, &owner, &secret)
+    }
+
+    fn renew_record(e: Env, caller: Address, key: RecordKeys, duration: u64) {
+        e.bump_core();
+        e.require_not_paused(&PauseScope::Registration);
+        caller.require_auth();
+
+        let core_data: CoreData = e.core_data().unwrap();
+        let mut domain: Domain = match e.record(&key) {
+            Some(Record::Domain(domain)) => domain,
+            Some(Record::SubDomain(_)) => panic_with_error!(&e, &ContractErrors::InvalidDomain),
+            None => panic_with_error!(&e, &ContractErrors::RecordDoesntExist),
+        };
+
+        if domain.exp_date < e.ledger().timestamp() {
+            if is_claimable(&e, &domain) {
+                panic_with_error!(&e, &ContractErrors::ExpiredDomain);
+            }
+
+            domain.owner.require_auth();
+        }
+
+        let quote: RenewalQuote = renewal_quote(&e, &core_data, &domain, duration);
+
+        if quote.collateral_payment > 0 {
+            token::Client::new(&e, &core_data.col_asset).transfer(
+                &caller,
+                &e.current_contract_address(),
+                &(quote.collateral_payment as i128),
+            );
+        } else if quote.collateral_refund > 0 {
+            token::Client::new(&e, &core_data.col_asset).transfer(
+                &e.current_contract_address(),
+                &domain.owner,
+                &(quote.collateral_refund as i128),
+            );
+        }
+
+        domain.exp_date = quote.exp_date;
+        domain.collateral = quote.collateral;
+
+        pay_duration_fee(
+            &e,
+            &core_data,
+            &caller,
+            &domain.node,
+            domain.collateral,
+            duration,
         );
 
-        e.set_record(&Record::Domain(Domain {
-            node: node_hash,
-            owner,
-            address,
-            exp_date,
-            collateral,
-            snapshot: e.ledger().timestamp(),
-        }));
+        emit_record_renewed(
+            &e,
+            &caller,
+            &domain.node,
+            &domain.exp_date,
+            &domain.collateral,
+        );
 
-        // TODO: add an event
+        e.set_record(&Record::Domain(domain));
+        e.bump_record(&key);
+    }
 
-        e.bump_record(&record_key);
+    fn rebalance_collateral(e: Env, caller: Address, node: BytesN<32>) {
+        e.bump_core();
+        e.require_not_paused(&PauseScope::Registration);
+        caller.require_auth();
+
+        let core_data: CoreData = e.core_data().unwrap();
+        let mut domain: Domain = match e.record(&RecordKeys::Record(node.clone())) {
+            Some(Record::Domain(domain)) => domain,
+            Some(Record::SubDomain(_)) => panic_with_error!(&e, &ContractErrors::InvalidDomain),
+            None => panic_with_error!(&e, &ContractErrors::RecordDoesntExist),
+        };
+
+        if domain.exp_date < e.ledger().timestamp() {
+            panic_with_error!(&e, &ContractErrors::ExpiredDomain);
+        }
+
+        let quote: RebalanceQuote = rebalance_quote(&e, &domain);
+
+        if quote.collateral_refund > 0 {
+            if caller != domain.owner {
+                panic_with_error!(&e, &ContractErrors::NotAuthorized);
+            }
+
+            token::Client::new(&e, &core_data.col_asset).transfer(
+                &e.current_contract_address(),
+                &domain.owner,
+                &(quote.collateral_refund as i128),
+            );
+        } else if quote.collateral_payment > 0 {
+            token::Client::new(&e, &core_data.col_asset).transfer(
+                &caller,
+                &e.current_contract_address(),
+                &(quote.collateral_payment as i128),
+            );
+        } else {
+            return;
+        }
+
+        emit_collateral_rebalanced(
+            &e,
+            &domain.node,
+            &caller,
+            &domain.collateral,
+            &quote.collateral,
+        );
+
+        domain.collateral = quote.collateral;
+        e.set_record(&Record::Domain(domain));
     }
 
     fn update_address(e: Env, key: RecordKeys, address: Address) {
         e.bump_core();
+        e.require_not_paused(&PauseScope::Registration);
         let record: Record = match e.record(&key) {
             Some(record) => record,
             None => panic_with_error!(&e, ContractErrors::RecordDoesntExist),
@@ -183,6 +890,7 @@ impl RegistryContractTrait for RegistryContract {
         if let Record::Domain(mut domain) = record {
             domain.owner.require_auth();
             domain.address = address;
+            emit_address_updated(&e, &domain.node, &domain.address);
             e.set_record(&Record::Domain(domain));
             e.bump_record(&key);
         } else {
@@ -192,35 +900,173 @@ impl RegistryContractTrait for RegistryContract {
 
     fn set_sub(e: Env, sub: Bytes, parent: RecordKeys, address: Address) {
         e.bump_core();
-
-        validate_domain(&e, &sub);
+        e.require_not_paused(&PauseScope::Subdomains);
 
         let parent_record: Record = e
             .record(&parent)
             .unwrap_or_else(|| panic_with_error!(&e, &ContractErrors::InvalidParent));
 
-        if let Record::Domain(domain) = parent_record {
-            domain.owner.require_auth();
+        let (parent_node, controller, root, depth): (BytesN<32>, Address, Domain, u32) =
+            match parent_record {
+                Record::Domain(domain) => (domain.node.clone(), domain.owner.clone(), domain, 0),
+                Record::SubDomain(parent_sub) => {
+                    let (root, depth) = validate_sub(&e, &parent_sub)
+                        .unwrap_or_else(|error| panic_with_error!(&e, &error));
+                    let controller: Address = sub_controller(&e, &parent_sub)
+                        .unwrap_or_else(|error| panic_with_error!(&e, &error));
+                    (parent_sub.node, controller, root, depth)
+                }
+            };
 
-            if domain.exp_date < e.ledger().timestamp() {
-                panic_with_error!(&e, &ContractErrors::ExpiredDomain);
+        controller.require_auth();
+
+        // Subdomains follow the name rules of the TLD of their root domain
+        let rules: NameRules = e
+            .record_name(&root.node)
+            .and_then(|name| e.tld_config(&name.tld))
+            .map(|config| config.name_rules)
+            .unwrap_or_default();
+        validate_domain(&e, &sub, &rules);
+
+        if root.exp_date < e.ledger().timestamp() {
+            panic_with_error!(&e, &ContractErrors::ExpiredDomain);
+        }
+
+        if depth + 1 > e.subdomains_config().max_depth {
+            panic_with_error!(&e, &ContractErrors::MaxDepthExceeded);
+        }
+
+        let node_hash: BytesN<32> = generate_node(&e, &sub, &(Bytes::from(parent_node.clone())));
+        let record_key: RecordKeys = RecordKeys::SubRecord(node_hash.clone());
+
+        // If the subdomain has its own owner we don't replace it unless it's outdated, in that case the owner is from an
+        // old snapshot, so we remove it
+        if let Some(Record::SubDomain(old_sub)) = e.record(&record_key) {
+            if e.sub_owner(&old_sub.node).is_some() {
+                if old_sub.snapshot == root.snapshot {
+                    panic_with_error!(&e, &ContractErrors::SubdomainOwned);
+                }
+
+                e.burn_sub_owner(&old_sub.node);
             }
+        }
 
-            let node_hash: BytesN<32> =
-                generate_node(&e, &sub, &(Bytes::from(domain.node.clone())));
-            let record_key: RecordKeys = RecordKeys::SubRecord(node_hash.clone());
+        emit_sub_record_set(&e, &node_hash, &sub, &parent_node, &address, &root.snapshot);
+        e.add_subdomain(&parent_node, &node_hash);
 
-            e.set_record(&Record::SubDomain(SubDomain {
-                node: node_hash,
-                parent: domain.node.clone(),
-                address,
-                snapshot: domain.snapshot,
-            }));
+        e.set_record(&Record::SubDomain(SubDomain {
+            node: node_hash,
+            parent: parent_node,
+            address,
+            snapshot: root.snapshot,
+        }));
 
-            e.bump_record(&record_key);
-        } else {
-            panic_with_error!(&e, &ContractErrors::InvalidParent)
+        e.bump_record(&record_key);
+    }
+
+    fn transfer_sub(e: Env, node: BytesN<32>, to: Address) {
+        e.bump_core();
+        e.require_not_paused(&PauseScope::Subdomains);
+        let sub: SubDomain = match e.record(&RecordKeys::SubRecord(node.clone())) {
+            Some(Record::SubDomain(sub)) => sub,
+            _ => panic_with_error!(&e, ContractErrors::RecordDoesntExist),
+        };
+
+        if let Err(error) = validate_sub(&e, &sub) {
+            panic_with_error!(&e, &error);
+        }
+
+        sub_controller(&e, &sub)
+            .unwrap_or_else(|error| panic_with_error!(&e, &error))
+            .require_auth();
+
+        emit_sub_record_transferred(&e, &sub.node, &e.sub_owner(&sub.node), &to);
+        e.set_sub_owner(&sub.node, &to);
+        e.bump_record(&RecordKeys::SubRecord(node));
+    }
+
+    fn update_sub_address(e: Env, node: BytesN<32>, address: Address) {
+        e.bump_core();
+        e.require_not_paused(&PauseScope::Subdomains);
+        let mut sub: SubDomain = match e.record(&RecordKeys::SubRecord(node.clone())) {
+            Some(Record::SubDomain(sub)) => sub,
+            _ => panic_with_error!(&e, ContractErrors::RecordDoesntExist),
+        };
+
+        if let Err(error) = validate_sub(&e, &sub) {
+            panic_with_error!(&e, &error);
         }
+
+        sub_controller(&e, &sub)
+            .unwrap_or_else(|error| panic_with_error!(&e, &error))
+            .require_auth();
+
+        sub.address = address;
+        emit_address_updated(&e, &sub.node, &sub.address);
+        e.set_record(&Record::SubDomain(sub));
+        e.bump_record(&RecordKeys::SubRecord(node));
+    }
+
+    fn revoke_sub(e: Env, node: BytesN<32>) {
+        e.bump_core();
+        let sub: SubDomain = match e.record(&RecordKeys::SubRecord(node.clone())) {
+            Some(Record::SubDomain(sub)) => sub,
+            _ => panic_with_error!(&e, ContractErrors::RecordDoesntExist),
+        };
+
+        if !e.subdomains_config().parent_can_revoke {
+            panic_with_error!(&e, &ContractErrors::RevokeDisabled);
+        }
+
+        if let Err(error) = validate_sub(&e, &sub) {
+            panic_with_error!(&e, &error);
+        }
+
+        parent_controller(&e, &sub)
+            .unwrap_or_else(|error| panic_with_error!(&e, &error))
+            .require_auth();
+
+        let old_owner: Address = e
+            .sub_owner(&sub.node)
+            .unwrap_or_else(|| panic_with_error!(&e, &ContractErrors::SubdomainNotOwned));
+
+        e.burn_sub_owner(&sub.node);
+        emit_sub_record_revoked(&e, &sub.node, &old_owner);
+    }
+
+    fn sub_owner(e: Env, node: BytesN<32>) -> Option<Address> {
+        e.bump_core();
+        e.sub_owner(&node)
+    }
+
+    fn subdomains_of(e: Env, parent: BytesN<32>, cursor: u32, limit: u32) -> Vec<BytesN<32>> {
+        e.bump_core();
+        let mut subdomains: Vec<BytesN<32>> = Vec::new(&e);
+
+        // If the parent is outdated, all its subdomains are outdated too
+        let snapshot: u64 = match e.record(&RecordKeys::Record(parent.clone())) {
+            Some(Record::Domain(domain)) => domain.snapshot,
+            _ => match e.record(&RecordKeys::SubRecord(parent.clone())) {
+                Some(Record::SubDomain(parent_sub)) => match validate_sub(&e, &parent_sub) {
+                    Ok((root, _)) => root.snapshot,
+                    Err(_) => return subdomains,
+                },
+                _ => return subdomains,
+            },
+        };
+
+        let end: u32 = e
+            .subdomains_count(&parent)
+            .min(cursor.saturating_add(limit.min(MAX_PAGE_SIZE)));
+        for position in cursor..end {
+            let node: BytesN<32> = e.subdomain(&parent, position).unwrap();
+            if let Some(Record::SubDomain(sub)) = e.record(&RecordKeys::SubRecord(node.clone())) {
+                if sub.snapshot == snapshot {
+                    subdomains.push_back(node);
+                }
+            }
+        }
+        subdomains
     }
 
     fn record(e: Env, key: RecordKeys) -> Option<Record> {
@@ -241,18 +1087,8 @@ impl RegistryContractTrait for RegistryContract {
                 Some(Record::Domain(domain))
             }
             Record::SubDomain(sub) => {
-                if let Record::Domain(domain) =
-                    e.record(&RecordKeys::Record(sub.parent.clone())).unwrap()
-                {
-                    if domain.exp_date < e.ledger().timestamp() {
-                        panic_with_error!(&e, &ContractErrors::ExpiredDomain);
-                    }
-
-                    if domain.snapshot != sub.snapshot {
-                        panic_with_error!(&e, &ContractErrors::OutdatedSub);
-                    }
-                } else {
-                    panic_with_error!(&e, &ContractErrors::InvalidParent);
+                if let Err(error) = validate_sub(&e, &sub) {
+                    panic_with_error!(&e, &error);
                 }
 
                 Some(Record::SubDomain(sub))
@@ -260,27 +1096,200 @@ impl RegistryContractTrait for RegistryContract {
         }
     }
 
+    fn resolve(e: Env, name: Bytes) -> Option<Address> {
+        e.bump_core();
+
+        match resolve_name(&e, &name) {
+            Ok(Record::Domain(domain)) => Some(domain.address),
+            Ok(Record::SubDomain(sub)) => Some(sub.address),
+            Err(_) => None,
+        }
+    }
+
+    fn resolve_record(e: Env, name: Bytes) -> Result<Record, ContractErrors> {
+        e.bump_core();
+        resolve_name(&e, &name)
+    }
+
     fn parse_domain(e: Env, domain: Bytes, tld: Bytes) -> BytesN<32> {
         e.bump_core();
         generate_node(&e, &domain, &tld)
     }
 
-    fn transfer(e: Env, key: RecordKeys, to: Address) {
+    fn quote_registration(e: Env, domain: Bytes, tld: Bytes, duration: u64) -> RegistrationQuote {
         e.bump_core();
-        let record: Record = match e.record(&key) {
-            Some(record) => record,
-            None => panic_with_error!(&e, ContractErrors::RecordDoesntExist),
+        registration_quote(&e, &domain, &tld, duration)
+    }
+
+    fn quote_renewal(e: Env, key: RecordKeys, duration: u64) -> RenewalQuote {
+        e.bump_core();
+
+        let domain: Domain = match e.record(&key) {
+            Some(Record::Domain(domain)) => domain,
+            Some(Record::SubDomain(_)) => panic_with_error!(&e, &ContractErrors::InvalidDomain),
+            None => panic_with_error!(&e, &ContractErrors::RecordDoesntExist),
         };
 
-        if let Record::Domain(mut domain) = record {
-            domain.owner.require_auth();
-            domain.owner = to;
-            domain.snapshot = e.ledger().timestamp();
-            e.set_record(&Record::Domain(domain));
-            e.bump_record(&key);
-        } else {
-            panic_with_error!(&e, ContractErrors::InvalidTransfer);
+        if is_claimable(&e, &domain) {
+            panic_with_error!(&e, &ContractErrors::ExpiredDomain);
+        }
+
+        renewal_quote(&e, &e.core_data().unwrap(), &domain, duration)
+    }
+
+    fn quote_offer(e: Env, node: BytesN<32>) -> Vec<OfferQuote> {
+        e.bump_core();
+
+        match e.record(&RecordKeys::Record(node)) {
+            Some(Record::Domain(domain)) => offer_quotes(&e, &domain),
+            Some(Record::SubDomain(_)) => panic_with_error!(&e, &ContractErrors::InvalidDomain),
+            None => panic_with_error!(&e, &ContractErrors::RecordDoesntExist),
+        }
+    }
+
+    fn quote_rebalance(e: Env, node: BytesN<32>) -> RebalanceQuote {
+        e.bump_core();
+
+        match e.record(&RecordKeys::Record(node)) {
+            Some(Record::Domain(domain)) => rebalance_quote(&e, &domain),
+            Some(Record::SubDomain(_)) => panic_with_error!(&e, &ContractErrors::InvalidDomain),
+            None => panic_with_error!(&e, &ContractErrors::RecordDoesntExist),
+        }
+    }
+
+    fn transfer(e: Env, from: Address, to: Address, token_id: BytesN<32>) {
+        e.bump_core();
+        e.require_not_paused(&PauseScope::Registration);
+        from.require_auth();
+
+        let domain: Domain = token_domain(&e, &token_id);
+        if domain.owner != from {
+            panic_with_error!(&e, &ContractErrors::NotAuthorized);
+        }
+
+        // Domains past their grace period can be liquidated at any moment, so they can't change hands anymore
+        if is_claimable(&e, &domain) {
+            panic_with_error!(&e, &ContractErrors::ExpiredDomain);
         }
+
+        transfer_domain(&e, domain, to);
+    }
+
+    fn approve(
+        e: Env,
+        approver: Address,
+        approved: Address,
+        token_id: BytesN<32>,
+        live_until_ledger: u32,
+    ) {
+        e.bump_core();
+        e.require_not_paused(&PauseScope::Registration);
+        approver.require_auth();
+
+        let domain: Domain = token_domain(&e, &token_id);
+        if domain.owner != approver && !e.is_operator(&domain.owner, &approver) {
+            panic_with_error!(&e, &ContractErrors::NotAuthorized);
+        }
+
+        // Domains past their grace period can be liquidated at any moment, so they can't change hands anymore
+        if is_claimable(&e, &domain) {
+            panic_with_error!(&e, &ContractErrors::ExpiredDomain);
+        }
+
+        if live_until_ledger < e.ledger().sequence() {
+            panic_with_error!(&e, &ContractErrors::InvalidExpiry);
+        }
+
+        e.set_approval(
+            &token_id,
+            &Approval {
+                spender: approved.clone(),
+                live_until_ledger,
+                snapshot: domain.snapshot,
+            },
+        );
+        emit_approval_set(&e, &token_id, &approved, &live_until_ledger);
+    }
+
+    fn revoke_approval(e: Env, caller: Address, node: BytesN<32>) {
+        e.bump_core();
+        caller.require_auth();
+
+        let domain: Domain = match e.record(&RecordKeys::Record(node.clone())) {
+            Some(Record::Domain(domain)) => domain,
+            Some(Record::SubDomain(_)) => panic_with_error!(&e, &ContractErrors::InvalidDomain),
+            None => panic_with_error!(&e, &ContractErrors::RecordDoesntExist),
+        };
+
+        if domain.owner != caller && !e.is_operator(&domain.owner, &caller) {
+            panic_with_error!(&e, &ContractErrors::NotAuthorized);
+        }
+
+        e.burn_approval(&node);
+        emit_approval_revoked(&e, &node);
+    }
+
+    fn approval(e: Env, node: BytesN<32>) -> Option<Approval> {
+        e.bump_core();
+
+        // Outdated and expired approvals are not returned
+        match e.record(&RecordKeys::Record(node)) {
+            Some(Record::Domain(domain)) => active_approval(&e, &domain),
+            _ => None,
+        }
+    }
+
+    fn set_approval_for_all(e: Env, owner: Address, operator: Address, approved: bool) {
+        e.bump_core();
+        owner.require_auth();
+
+        // Removing an operator is allowed while the contract is paused
+        if approved {
+            e.require_not_paused(&PauseScope::Registration);
+        }
+
+        e.set_operator(&owner, &operator, approved);
+        emit_operator_updated(&e, &owner, &operator, &approved);
+    }
+
+    fn is_approved_for_all(e: Env, owner: Address, operator: Address) -> bool {
+        e.bump_core();
+        e.is_operator(&owner, &operator)
+    }
+
+    fn transfer_from(e: Env, spender: Address, from: Address, to: Address, token_id: BytesN<32>) {
+        e.bump_core();
+        e.require_not_paused(&PauseScope::Registration);
+        spender.require_auth();
+
+        let domain: Domain = token_domain(&e, &token_id);
+        if domain.owner != from || !can_transfer(&e, &domain, &spender) {
+            panic_with_error!(&e, &ContractErrors::NotAuthorized);
+        }
+
+        // Domains past their grace period can be liquidated at any moment, so they can't change hands anymore
+        if is_claimable(&e, &domain) {
+            panic_with_error!(&e, &ContractErrors::ExpiredDomain);
+        }
+
+        transfer_domain(&e, domain, to);
+    }
+
+    fn liquidate(e: Env, caller: Address, node: BytesN<32>) {
+        e.bump_core();
+        caller.require_auth();
+
+        let domain: Domain = match e.record(&RecordKeys::Record(node)) {
+            Some(Record::Domain(domain)) => domain,
+            Some(Record::SubDomain(_)) => panic_with_error!(&e, &ContractErrors::InvalidDomain),
+            None => panic_with_error!(&e, &ContractErrors::RecordDoesntExist),
+        };
+
+        if !is_claimable(&e, &domain) {
+            panic_with_error!(&e, &ContractErrors::NotLiquidatable);
+        }
+
+        liquidate_domain(&e, &e.core_data().unwrap(), &domain, &caller);
     }
 
     fn burn_record(e: Env, key: RecordKeys) {
@@ -294,30 +1303,129 @@ impl RegistryContractTrait for RegistryContract {
         match record {
             Record::Domain(domain) => {
                 domain.owner.require_auth();
+
+                // The collateral of a claimable domain is released with `liquidate` so the protocol share and the
+                // keeper reward are still paid
+                if is_claimable(&e, &domain) {
+                    panic_with_error!(&e, &ContractErrors::ExpiredDomain);
+                }
+
                 e.burn_record(&RecordKeys::Record(domain.node.clone()));
+                e.burn_record_name(&domain.node);
+                e.burn_approval(&domain.node);
+                e.remove_owned_domain(&domain.owner, &domain.node);
+                let mut budget: u32 = MAX_BURNED_SUBDOMAINS;
+                burn_subdomains(&e, &domain.node, &mut budget);
+                burn_domain_offers(&e, &core_data, &domain);
                 token::Client::new(&e, &core_data.col_asset).transfer(
                     &e.current_contract_address(),
                     &domain.owner,
                     &(domain.collateral as i128),
                 );
+                emit_record_burned(&e, &domain.node, &domain.owner, &domain.collateral);
             }
             Record::SubDomain(sub) => {
-                if let Record::Domain(domain) =
-                    e.record(&RecordKeys::Record(sub.parent.clone())).unwrap()
-                {
-                    domain.owner.require_auth();
-                } else {
-                    panic_with_error!(&e, &ContractErrors::InvalidParent);
-                }
-                e.burn_record(&RecordKeys::Record(sub.node.clone()));
+                sub_controller(&e, &sub)
+                    .unwrap_or_else(|error| panic_with_error!(&e, &error))
+                    .require_auth();
+                let mut budget: u32 = MAX_BURNED_SUBDOMAINS;
+                burn_subdomains(&e, &sub.node, &mut budget);
+                burn_sub(&e, &sub.parent, &sub.node);
             }
         }
+    }
+
+    fn burn_orphan_subdomains(e: Env, parent: BytesN<32>) -> u32 {
+        e.bump_core();
+
+        // The subdomains of a domain past its grace period are outdated, so they can be burned before the domain is
+        // registered again
+        let parent_exists: bool = match e.record(&RecordKeys::Record(parent.clone())) {
+            Some(Record::Domain(domain)) => !is_claimable(&e, &domain),
+            _ => e.record(&RecordKeys::SubRecord(parent.clone())).is_some(),
+        };
+
+        if parent_exists {
+            panic_with_error!(&e, &ContractErrors::InvalidParent);
+        }
+
+        let mut budget: u32 = MAX_BURNED_SUBDOMAINS;
+        burn_subdomains(&e, &parent, &mut budget);
+        e.subdomains_count(&parent)
+    }
+
+    fn set_nft_config(e: Env, caller: Address, config: NftConfig) {
+        e.bump_core();
+        e.require_role(&Role::TldManager, &caller);
+
+        if config.name.is_empty()
+            || config.symbol.is_empty()
+            || config.base_uri.len() > MAX_BASE_URI_LENGTH
+        {
+            panic_with_error!(&e, &ContractErrors::InvalidConfig);
+        }
+
+        e.set_nft_config(&config);
+        emit_nft_config_updated(&e, &config);
+    }
+
+    fn name(e: Env) -> String {
+        e.bump_core();
+        e.nft_config().name
+    }
+
+    fn symbol(e: Env) -> String {
+        e.bump_core();
+        e.nft_config().symbol
+    }
 
-        // TODO: Add event
+    fn balance(e: Env, owner: Address) -> u32 {
+        e.bump_core();
+        e.domains_balance(&owner)
+    }
+
+    fn owner_of(e: Env, node: BytesN<32>) -> Address {
+        e.bump_core();
+        token_domain(&e, &node).owner
+    }
+
+    fn get_approved(e: Env, node: BytesN<32>) -> Option<Address> {
+        e.bump_core();
+        active_approval(&e, &token_domain(&e, &node)).map(|approval| approval.spender)
+    }
+
+    fn token_uri(e: Env, node: BytesN<32>) -> Option<String> {
+        e.bump_core();
+        let domain: Domain = token_domain(&e, &node);
+        token_uri(&e, &e.nft_config(), &domain.node)
+    }
+
+    fn token_metadata(e: Env, node: BytesN<32>) -> TokenMetadata {
+        e.bump_core();
+        token_metadata(&e, &e.nft_config(), &token_domain(&e, &node))
+    }
+
+    fn domains_of(e: Env, owner: Address, cursor: u32, limit: u32) -> Vec<BytesN<32>> {
+        e.bump_core();
+        let end: u32 = e
+            .domains_balance(&owner)
+            .min(cursor.saturating_add(limit.min(MAX_PAGE_SIZE)));
+
+        let mut domains: Vec<BytesN<32>> = Vec::new(&e);
+        for position in cursor..end {
+            domains.push_back(e.owned_domain(&owner, position).unwrap());
+        }
+        domains
+    }
+
+    fn domain_count(e: Env, owner: Address) -> u32 {
+        e.bump_core();
+        e.domains_balance(&owner)
     }
 
     fn set_offer(e: Env, caller: Address, node: BytesN<32>, amount: u128) {
         e.bump_core();
+        e.require_not_paused(&PauseScope::Marketplace);
         caller.require_auth();
 
         let record: Record = match e.record(&RecordKeys::Record(node.clone())) {
@@ -333,6 +1441,15 @@ impl RegistryContractTrait for RegistryContract {
             Record::SubDomain(_) => panic_with_error!(&e, ContractErrors::InvalidDomain),
         };
 
+        if let TldStatus::Sunset(_) = domain_tld_status(&e, &domain.node) {
+            panic_with_error!(&e, &ContractErrors::TldClosed);
+        }
+
+        // Domains past their grace period can be liquidated at any moment, so they can't change hands anymore
+        if is_claimable(&e, &domain) {
+            panic_with_error!(&e, &ContractErrors::ExpiredDomain);
+        }
+
         let is_sale: bool = domain.owner == caller;
 
         if !is_sale && amount <= domain.collateral {
@@ -379,6 +1496,7 @@ impl RegistryContractTrait for RegistryContract {
 
     fn take_offer(e: Env, caller: Address, node: BytesN<32>) {
         e.bump_core();
+        e.require_not_paused(&PauseScope::Marketplace);
         caller.require_auth();
 
         let mut domain: Domain = match e.record(&RecordKeys::Record(node.clone())).unwrap() {
@@ -386,6 +1504,15 @@ impl RegistryContractTrait for RegistryContract {
             Record::SubDomain(_) => panic_with_error!(&e, &ContractErrors::InvalidDomain),
         };
 
+        // Domains past their grace period can be liquidated at any moment, so they can't change hands anymore
+        if is_claimable(&e, &domain) {
+            panic_with_error!(&e, &ContractErrors::ExpiredDomain);
+        }
+
+        if let TldStatus::Sunset(_) = domain_tld_status(&e, &domain.node) {
+            panic_with_error!(&e, &ContractErrors::TldClosed);
+        }
+
         // In this case the condition is the other way around because the seller (domain owner)
         // is accepting a buy Offer instead of making its own sale offer
         let is_seller: bool = domain.owner != caller;
@@ -400,26 +1527,39 @@ impl RegistryContractTrait for RegistryContract {
         let core_data: CoreData = e.core_data().unwrap();
         let offers_config: OffersConfig = e.offers_config().unwrap();
 
+        // Undercollateralized domains are rebalanced with the amount of the offer, so the seller doesn't need to top up
+        // the collateral before the sale
+        let top_up: u128 = missing_collateral(&e, &domain).unwrap_or(0);
+        let collateral: u128 = domain.collateral + top_up;
+
         match offer {
             Offer::BuyOffer(buy_offer) => {
                 if domain.snapshot != buy_offer.snapshot {
                     panic_with_error!(&e, &ContractErrors::OutdatedOffer);
                 }
 
-                let profit: u128 = buy_offer.amount - domain.collateral;
+                // Renewals and rebalances can raise the collateral after the offer was made
+                let profit: u128 = buy_offer
+                    .amount
+                    .checked_sub(collateral)
+                    .unwrap_or_else(|| panic_with_error!(&e, &ContractErrors::InvalidOfferAmount));
                 // TODO: Test minimal fee (for example a profit of 0_0000001)
-                let fee: u128 = div_ceil(profit * offers_config.fee, 100_0000000);
+                let fee: u128 = offer_fee(&offers_config, profit);
 
+                // The top up stays in the contract as part of the collateral
                 token::Client::new(&e, &core_data.col_asset).transfer(
                     &e.current_contract_address(),
                     &domain.owner,
-                    &((buy_offer.amount - fee) as i128),
+                    &((buy_offer.amount - top_up - fee) as i128),
                 );
 
-                token::Client::new(&e, &core_data.col_asset).transfer(
+                transfer_protocol_fee(
+                    &e,
+                    &core_data.col_asset,
                     &e.current_contract_address(),
                     &offers_config.fee_taker,
-                    &(fee as i128),
+                    &domain.node,
+                    fee,
                 );
 
                 emit_offer_accepted(
@@ -430,9 +1570,23 @@ impl RegistryContractTrait for RegistryContract {
                     &buy_offer.amount,
                 );
 
+                if top_up > 0 {
+                    emit_collateral_rebalanced(
+                        &e,
+                        &domain.node,
+                        &buy_offer.buyer,
+                        &domain.collateral,
+                        &collateral,
+                    );
+                }
+
+                e.remove_owned_domain(&domain.owner, &domain.node);
+                e.add_owned_domain(&buy_offer.buyer, &domain.node);
                 domain.owner = buy_offer.buyer.clone();
                 domain.address = buy_offer.buyer;
+                domain.collateral = collateral;
                 domain.snapshot = e.ledger().timestamp();
+                e.burn_approval(&domain.node);
                 e.set_record(&Record::Domain(domain));
                 e._offers().burn(&OffersDataKeys::BuyOffer(buy_offer.node));
             }
@@ -441,26 +1595,55 @@ impl RegistryContractTrait for RegistryContract {
                     panic_with_error!(&e, &ContractErrors::OutdatedOffer);
                 }
 
-                let profit: u128 = sale_offer.amount - domain.collateral;
-                let fee: u128 = div_ceil(profit * offers_config.fee, 100_0000000);
+                // Renewals and rebalances can raise the collateral after the offer was made
+                let profit: u128 = sale_offer
+                    .amount
+                    .checked_sub(collateral)
+                    .unwrap_or_else(|| panic_with_error!(&e, &ContractErrors::InvalidOfferAmount));
+                let fee: u128 = offer_fee(&offers_config, profit);
 
                 token::Client::new(&e, &core_data.col_asset).transfer(
                     &caller,
                     &domain.owner,
-                    &((sale_offer.amount - fee) as i128),
+                    &((sale_offer.amount - top_up - fee) as i128),
                 );
 
-                token::Client::new(&e, &core_data.col_asset).transfer(
+                if top_up > 0 {
+                    token::Client::new(&e, &core_data.col_asset).transfer(
+                        &caller,
+                        &e.current_contract_address(),
+                        &(top_up as i128),
+                    );
+                }
+
+                transfer_protocol_fee(
+                    &e,
+                    &core_data.col_asset,
                     &caller,
                     &offers_config.fee_taker,
-                    &(fee as i128),
+                    &domain.node,
+                    fee,
                 );
 
                 emit_offer_accepted(&e, &caller, &domain.owner, &domain.node, &sale_offer.amount);
 
+                if top_up > 0 {
+                    emit_collateral_rebalanced(
+                        &e,
+                        &domain.node,
+                        &caller,
+                        &domain.collateral,
+                        &collateral,
+                    );
+                }
+
+                e.remove_owned_domain(&domain.owner, &domain.node);
+                e.add_owned_domain(&caller, &domain.node);
                 domain.owner = caller.clone();
                 domain.address = caller;
+                domain.collateral = collateral;
                 domain.snapshot = e.ledger().timestamp();
+                e.burn_approval(&domain.node);
                 e.set_record(&Record::Domain(domain));
                 e._offers()
                     .burn(&OffersDataKeys::SaleOffer(sale_offer.node));
@@ -487,6 +1670,7 @@ impl RegistryContractTrait for RegistryContract {
                 );
 
                 e._offers().burn(&key);
+                emit_buy_offer_burned(&e, &buy_offer.node, &buy_offer.buyer, &buy_offer.amount);
             }
             Offer::SaleOffer(sale_offer) => {
                 let domain: Record = e
@@ -499,6 +1683,7 @@ impl RegistryContractTrait for RegistryContract {
                     Record::Domain(domain) => {
                         domain.owner.require_auth();
                         e._offers().burn(&key);
+                        emit_sale_offer_burned(&e, &domain.node, &domain.owner);
                     }
                     Record::SubDomain(_) => panic_with_error!(&e, &ContractErrors::InvalidDomain),
                 };
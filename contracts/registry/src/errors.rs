@@ -17,4 +17,35 @@ pub enum ContractErrors {
     InvalidOfferAmount = 11,
     OutdatedOffer = 12,
     OfferDoesntExist = 13,
+    InvalidConfig = 14,
+    MaxDepthExceeded = 15,
+    SubdomainOwned = 16,
+    SubdomainNotOwned = 17,
+    RevokeDisabled = 18,
+    NoPendingAdmin = 19,
+    NotAuthorized = 20,
+    ContractPaused = 21,
+    OracleUnavailable = 22,
+    StalePrice = 23,
+    PriceOutOfBounds = 24,
+    TldAlreadyExists = 25,
+    TldClosed = 26,
+    NameTooShort = 27,
+    NameTooLong = 28,
+    InvalidCharacter = 29,
+    InvalidHyphen = 30,
+    BlockedName = 31,
+    InvalidPunycode = 32,
+    MixedScripts = 33,
+    ConfusableName = 34,
+    CommitmentNotFound = 35,
+    CommitmentTooRecent = 36,
+    CommitmentExpired = 37,
+    CommitmentAlreadyExists = 38,
+    CommitmentRequired = 39,
+    InvalidExpiry = 40,
+    NotLiquidatable = 41,
+    Undercollateralized = 42,
+    UnpricedDomain = 43,
+    PendingSubdomains = 44,
 }
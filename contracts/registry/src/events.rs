@@ -1,10 +1,459 @@
-use soroban_sdk::{contracttype, Address, BytesN, Env};
+use crate::storage::core::{
+    CollateralConfig, CommitmentsConfig, DurationConfig, LiquidationConfig, NftConfig,
+    OracleConfig, PauseScope,
+};
+use crate::storage::pricing::PricingTable;
+use crate::storage::record::{Domain, RecordName};
+use crate::storage::roles::Role;
+use crate::storage::tlds::{TldConfig, TldStatus};
+use soroban_sdk::{contracttype, Address, Bytes, BytesN, Env, Vec};
 
+// Events related to a record use the layout (topic, node) so indexers can follow a node using only the topics.
+// Config events only use the (topic,) layout.
 #[contracttype]
-enum EventTopics {
+pub enum EventTopics {
+    // Config events
+    Initialized,
+    OffersConfigUpdated,
+    OracleUpdated,
+    OracleConfigUpdated,
+    Upgraded,
+    TldCreated,
+    TldUpdated,
+    TldStatusUpdated,
+    ExpirationConfigUpdated,
+    LiquidationConfigUpdated,
+    CollateralConfigUpdated,
+    DurationConfigUpdated,
+    CommitmentsConfigUpdated,
+    NftConfigUpdated,
+    SubdomainsConfigUpdated,
+    RoleGranted,
+    RoleRevoked,
+    Paused,
+    Unpaused,
+    PricingTableUpdated,
+    PremiumPriceUpdated,
+    BlockedNameUpdated,
+
+    // Record events
+    CommitmentSet,
+    RecordSet,
+    RecordReleased,
+    RecordLiquidated,
+    RecordRenewed,
+    CollateralRebalanced,
+    DurationFeePaid,
+    AddressUpdated,
+    RecordTransferred,
+    ApprovalSet,
+    ApprovalRevoked,
+    OperatorUpdated,
+    SubRecordSet,
+    SubRecordTransferred,
+    SubRecordRevoked,
+    RecordBurned,
+    SubRecordBurned,
+
+    // Offers events
+    SaleOfferSet,
+    BuyOfferSet,
+    SaleOfferBurned,
+    BuyOfferBurned,
     OfferAccepted,
 }
 
+pub fn emit_initialized(
+    e: &Env,
+    adm: &Address,
+    col_asset: &Address,
+    min_duration: &u64,
+    allowed_tlds: &Vec<Bytes>,
+) {
+    e.events().publish(
+        (EventTopics::Initialized,),
+        (
+            adm.clone(),
+            col_asset.clone(),
+            min_duration.clone(),
+            allowed_tlds.clone(),
+        ),
+    );
+}
+
+pub fn emit_offers_config_updated(e: &Env, fee_taker: &Address, fee: &u128) {
+    e.events().publish(
+        (EventTopics::OffersConfigUpdated,),
+        (fee_taker.clone(), fee.clone()),
+    );
+}
+
+pub fn emit_oracle_updated(e: &Env, oracle: &Address) {
+    e.events()
+        .publish((EventTopics::OracleUpdated,), oracle.clone());
+}
+
+pub fn emit_oracle_config_updated(e: &Env, config: &OracleConfig) {
+    e.events()
+        .publish((EventTopics::OracleConfigUpdated,), config.clone());
+}
+
+pub fn emit_upgraded(e: &Env, hash: &BytesN<32>) {
+    e.events().publish((EventTopics::Upgraded,), hash.clone());
+}
+
+pub fn emit_tld_created(e: &Env, tld: &Bytes, config: &TldConfig) {
+    e.events()
+        .publish((EventTopics::TldCreated,), (tld.clone(), config.clone()));
+}
+
+pub fn emit_tld_updated(e: &Env, tld: &Bytes, config: &TldConfig) {
+    e.events()
+        .publish((EventTopics::TldUpdated,), (tld.clone(), config.clone()));
+}
+
+pub fn emit_tld_status_updated(e: &Env, tld: &Bytes, status: &TldStatus) {
+    e.events().publish(
+        (EventTopics::TldStatusUpdated,),
+        (tld.clone(), status.clone()),
+    );
+}
+
+pub fn emit_expiration_config_updated(e: &Env, grace_period: &u64, protocol_share: &u128) {
+    e.events().publish(
+        (EventTopics::ExpirationConfigUpdated,),
+        (grace_period.clone(), protocol_share.clone()),
+    );
+}
+
+pub fn emit_liquidation_config_updated(e: &Env, config: &LiquidationConfig) {
+    e.events()
+        .publish((EventTopics::LiquidationConfigUpdated,), config.clone());
+}
+
+pub fn emit_collateral_config_updated(e: &Env, config: &CollateralConfig) {
+    e.events()
+        .publish((EventTopics::CollateralConfigUpdated,), config.clone());
+}
+
+pub fn emit_duration_config_updated(e: &Env, config: &DurationConfig) {
+    e.events()
+        .publish((EventTopics::DurationConfigUpdated,), config.clone());
+}
+
+pub fn emit_commitments_config_updated(e: &Env, config: &CommitmentsConfig) {
+    e.events()
+        .publish((EventTopics::CommitmentsConfigUpdated,), config.clone());
+}
+
+pub fn emit_nft_config_updated(e: &Env, config: &NftConfig) {
+    e.events()
+        .publish((EventTopics::NftConfigUpdated,), config.clone());
+}
+
+pub fn emit_subdomains_config_updated(e: &Env, max_depth: &u32, parent_can_revoke: &bool) {
+    e.events().publish(
+        (EventTopics::SubdomainsConfigUpdated,),
+        (max_depth.clone(), parent_can_revoke.clone()),
+    );
+}
+
+pub fn emit_role_granted(e: &Env, role: &Role, account: &Address) {
+    e.events()
+        .publish((EventTopics::RoleGranted,), (role.clone(), account.clone()));
+}
+
+pub fn emit_role_revoked(e: &Env, role: &Role, account: &Address) {
+    e.events()
+        .publish((EventTopics::RoleRevoked,), (role.clone(), account.clone()));
+}
+
+pub fn emit_paused(e: &Env, scope: &PauseScope) {
+    e.events().publish((EventTopics::Paused,), scope.clone());
+}
+
+pub fn emit_unpaused(e: &Env, scope: &PauseScope) {
+    e.events().publish((EventTopics::Unpaused,), scope.clone());
+}
+
+// A `None` TLD is the default table and a `None` table means the table was removed
+pub fn emit_pricing_table_updated(e: &Env, tld: &Option<Bytes>, table: &Option<PricingTable>) {
+    e.events().publish(
+        (EventTopics::PricingTableUpdated,),
+        (tld.clone(), table.clone()),
+    );
+}
+
+pub fn emit_blocked_name_updated(e: &Env, node: &BytesN<32>, blocked: &bool) {
+    e.events().publish(
+        (EventTopics::BlockedNameUpdated, node.clone()),
+        (blocked.clone(), e.ledger().timestamp()),
+    );
+}
+
+pub fn emit_premium_price_updated(e: &Env, node: &BytesN<32>, price: &Option<u128>) {
+    e.events().publish(
+        (EventTopics::PremiumPriceUpdated, node.clone()),
+        (price.clone(), e.ledger().timestamp()),
+    );
+}
+
+// Commitments don't have a node yet, so the commitment is used in the topics instead
+pub fn emit_commitment_set(e: &Env, commitment: &BytesN<32>) {
+    e.events().publish(
+        (EventTopics::CommitmentSet, commitment.clone()),
+        (e.ledger().sequence(), e.ledger().timestamp()),
+    );
+}
+
+// The snapshot of a new record is always the timestamp of the event
+pub fn emit_record_set(e: &Env, domain: &Domain, name: &RecordName) {
+    e.events().publish(
+        (EventTopics::RecordSet, domain.node.clone()),
+        (
+            name.domain.clone(),
+            name.tld.clone(),
+            domain.owner.clone(),
+            domain.address.clone(),
+            domain.exp_date.clone(),
+            domain.collateral.clone(),
+            e.ledger().timestamp(),
+        ),
+    );
+}
+
+// Emitted when an expired domain is registered again and its old collateral is released
+pub fn emit_record_released(
+    e: &Env,
+    node: &BytesN<32>,
+    old_owner: &Address,
+    owner_amount: &u128,
+    protocol_amount: &u128,
+) {
+    e.events().publish(
+        (EventTopics::RecordReleased, node.clone()),
+        (
+            old_owner.clone(),
+            owner_amount.clone(),
+            protocol_amount.clone(),
+            e.ledger().timestamp(),
+        ),
+    );
+}
+
+pub fn emit_record_liquidated(
+    e: &Env,
+    node: &BytesN<32>,
+    old_owner: &Address,
+    keeper: &Address,
+    owner_amount: &u128,
+    keeper_reward: &u128,
+    protocol_amount: &u128,
+) {
+    e.events().publish(
+        (EventTopics::RecordLiquidated, node.clone()),
+        (
+            old_owner.clone(),
+            keeper.clone(),
+            owner_amount.clone(),
+            keeper_reward.clone(),
+            protocol_amount.clone(),
+            e.ledger().timestamp(),
+        ),
+    );
+}
+
+pub fn emit_duration_fee_paid(e: &Env, node: &BytesN<32>, payer: &Address, amount: &u128) {
+    e.events().publish(
+        (EventTopics::DurationFeePaid, node.clone()),
+        (payer.clone(), amount.clone(), e.ledger().timestamp()),
+    );
+}
+
+pub fn emit_record_renewed(
+    e: &Env,
+    caller: &Address,
+    node: &BytesN<32>,
+    exp_date: &u64,
+    collateral: &u128,
+) {
+    e.events().publish(
+        (EventTopics::RecordRenewed, node.clone()),
+        (
+            caller.clone(),
+            exp_date.clone(),
+            collateral.clone(),
+            e.ledger().timestamp(),
+        ),
+    );
+}
+
+pub fn emit_collateral_rebalanced(
+    e: &Env,
+    node: &BytesN<32>,
+    caller: &Address,
+    old_collateral: &u128,
+    collateral: &u128,
+) {
+    e.events().publish(
+        (EventTopics::CollateralRebalanced, node.clone()),
+        (
+            caller.clone(),
+            old_collateral.clone(),
+            collateral.clone(),
+            e.ledger().timestamp(),
+        ),
+    );
+}
+
+// The node can be either a domain or a subdomain node
+pub fn emit_address_updated(e: &Env, node: &BytesN<32>, address: &Address) {
+    e.events().publish(
+        (EventTopics::AddressUpdated, node.clone()),
+        (address.clone(), e.ledger().timestamp()),
+    );
+}
+
+// The new snapshot of the record is the timestamp of the event
+pub fn emit_record_transferred(e: &Env, node: &BytesN<32>, from: &Address, to: &Address) {
+    e.events().publish(
+        (EventTopics::RecordTransferred, node.clone()),
+        (from.clone(), to.clone(), e.ledger().timestamp()),
+    );
+}
+
+pub fn emit_approval_set(e: &Env, node: &BytesN<32>, spender: &Address, live_until_ledger: &u32) {
+    e.events().publish(
+        (EventTopics::ApprovalSet, node.clone()),
+        (
+            spender.clone(),
+            live_until_ledger.clone(),
+            e.ledger().timestamp(),
+        ),
+    );
+}
+
+pub fn emit_approval_revoked(e: &Env, node: &BytesN<32>) {
+    e.events().publish(
+        (EventTopics::ApprovalRevoked, node.clone()),
+        e.ledger().timestamp(),
+    );
+}
+
+// Operators are not related to a node, so the owner is used in the topics instead
+pub fn emit_operator_updated(e: &Env, owner: &Address, operator: &Address, approved: &bool) {
+    e.events().publish(
+        (EventTopics::OperatorUpdated, owner.clone()),
+        (operator.clone(), approved.clone(), e.ledger().timestamp()),
+    );
+}
+
+pub fn emit_sub_record_set(
+    e: &Env,
+    node: &BytesN<32>,
+    sub: &Bytes,
+    parent: &BytesN<32>,
+    address: &Address,
+    snapshot: &u64,
+) {
+    e.events().publish(
+        (EventTopics::SubRecordSet, node.clone()),
+        (
+            sub.clone(),
+            parent.clone(),
+            address.clone(),
+            snapshot.clone(),
+            e.ledger().timestamp(),
+        ),
+    );
+}
+
+// The `from` value is `None` when the subdomain was managed by the controller of its parent
+pub fn emit_sub_record_transferred(
+    e: &Env,
+    node: &BytesN<32>,
+    from: &Option<Address>,
+    to: &Address,
+) {
+    e.events().publish(
+        (EventTopics::SubRecordTransferred, node.clone()),
+        (from.clone(), to.clone(), e.ledger().timestamp()),
+    );
+}
+
+pub fn emit_sub_record_revoked(e: &Env, node: &BytesN<32>, old_owner: &Address) {
+    e.events().publish(
+        (EventTopics::SubRecordRevoked, node.clone()),
+        (old_owner.clone(), e.ledger().timestamp()),
+    );
+}
+
+pub fn emit_record_burned(e: &Env, node: &BytesN<32>, owner: &Address, collateral: &u128) {
+    e.events().publish(
+        (EventTopics::RecordBurned, node.clone()),
+        (owner.clone(), collateral.clone(), e.ledger().timestamp()),
+    );
+}
+
+pub fn emit_sub_record_burned(e: &Env, node: &BytesN<32>, parent: &BytesN<32>) {
+    e.events().publish(
+        (EventTopics::SubRecordBurned, node.clone()),
+        (parent.clone(), e.ledger().timestamp()),
+    );
+}
+
+pub fn emit_sale_offer_set(
+    e: &Env,
+    node: &BytesN<32>,
+    seller: &Address,
+    amount: &u128,
+    snapshot: &u64,
+) {
+    e.events().publish(
+        (EventTopics::SaleOfferSet, node.clone()),
+        (
+            seller.clone(),
+            amount.clone(),
+            snapshot.clone(),
+            e.ledger().timestamp(),
+        ),
+    );
+}
+
+pub fn emit_buy_offer_set(
+    e: &Env,
+    node: &BytesN<32>,
+    buyer: &Address,
+    amount: &u128,
+    snapshot: &u64,
+) {
+    e.events().publish(
+        (EventTopics::BuyOfferSet, node.clone()),
+        (
+            buyer.clone(),
+            amount.clone(),
+            snapshot.clone(),
+            e.ledger().timestamp(),
+        ),
+    );
+}
+
+pub fn emit_sale_offer_burned(e: &Env, node: &BytesN<32>, seller: &Address) {
+    e.events().publish(
+        (EventTopics::SaleOfferBurned, node.clone()),
+        (seller.clone(), e.ledger().timestamp()),
+    );
+}
+
+// The amount is the value returned to the buyer
+pub fn emit_buy_offer_burned(e: &Env, node: &BytesN<32>, buyer: &Address, amount: &u128) {
+    e.events().publish(
+        (EventTopics::BuyOfferBurned, node.clone()),
+        (buyer.clone(), amount.clone(), e.ledger().timestamp()),
+    );
+}
+
+// It keeps its original topics layout (without the node) so indexers that filter on it keep working
 pub fn emit_offer_accepted(
     e: &Env,
     buyer: &Address,
@@ -0,0 +1,77 @@
+use soroban_sdk::{contracttype, Address, BytesN, Env};
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Approval {
+    // The address that can transfer the domain on behalf of its owner
+    pub spender: Address,
+
+    // The last ledger in which the approval is valid
+    pub live_until_ledger: u32,
+
+    // The snapshot is taken from the domain, once the domain changes (for example after a transfer) the approval is
+    // invalid
+    pub snapshot: u64,
+}
+
+#[contracttype]
+pub enum ApprovalsDataKeys {
+    // Persistent keys
+    Approval(BytesN<32>),
+    Operator((Address, Address)),
+}
+
+pub trait ApprovalsEntity {
+    fn set_approval(&self, node: &BytesN<32>, approval: &Approval);
+    fn approval(&self, node: &BytesN<32>) -> Option<Approval>;
+    fn burn_approval(&self, node: &BytesN<32>);
+    fn set_operator(&self, owner: &Address, operator: &Address, approved: bool);
+    fn is_operator(&self, owner: &Address, operator: &Address) -> bool;
+}
+
+impl ApprovalsEntity for Env {
+    fn set_approval(&self, node: &BytesN<32>, approval: &Approval) {
+        let key: ApprovalsDataKeys = ApprovalsDataKeys::Approval(node.clone());
+        self.storage().persistent().set(&key, approval);
+        self.storage().persistent().extend_ttl(
+            &key,
+            17280,
+            self.ledger().sequence() + (17280 * 30),
+        );
+    }
+
+    fn approval(&self, node: &BytesN<32>) -> Option<Approval> {
+        self.storage()
+            .persistent()
+            .get(&ApprovalsDataKeys::Approval(node.clone()))
+    }
+
+    fn burn_approval(&self, node: &BytesN<32>) {
+        self.storage()
+            .persistent()
+            .remove(&ApprovalsDataKeys::Approval(node.clone()));
+    }
+
+    fn set_operator(&self, owner: &Address, operator: &Address, approved: bool) {
+        let key: ApprovalsDataKeys = ApprovalsDataKeys::Operator((owner.clone(), operator.clone()));
+        if approved {
+            self.storage().persistent().set(&key, &true);
+            self.storage().persistent().extend_ttl(
+                &key,
+                17280,
+                self.ledger().sequence() + (17280 * 30),
+            );
+        } else {
+            self.storage().persistent().remove(&key);
+        }
+    }
+
+    fn is_operator(&self, owner: &Address, operator: &Address) -> bool {
+        self.storage()
+            .persistent()
+            .has(&ApprovalsDataKeys::Operator((
+                owner.clone(),
+                operator.clone(),
+            )))
+    }
+}
@@ -0,0 +1,39 @@
+use soroban_sdk::{contracttype, BytesN, Env};
+
+#[contracttype]
+pub enum CommitmentsDataKeys {
+    // Temporary keys, the value is the ledger sequence where the commitment was made
+    Commitment(BytesN<32>),
+}
+
+pub trait CommitmentsEntity {
+    fn set_commitment(&self, commitment: &BytesN<32>, max_age: u32);
+    fn commitment(&self, commitment: &BytesN<32>) -> Option<u32>;
+    fn remove_commitment(&self, commitment: &BytesN<32>);
+}
+
+impl CommitmentsEntity for Env {
+    // Commitments are saved in the temporary storage and they live as long as they can be revealed, so stale commitments
+    // are removed by the network without any action from the users
+    fn set_commitment(&self, commitment: &BytesN<32>, max_age: u32) {
+        let key: CommitmentsDataKeys = CommitmentsDataKeys::Commitment(commitment.clone());
+        self.storage()
+            .temporary()
+            .set(&key, &self.ledger().sequence());
+        self.storage()
+            .temporary()
+            .extend_ttl(&key, max_age, max_age);
+    }
+
+    fn commitment(&self, commitment: &BytesN<32>) -> Option<u32> {
+        self.storage()
+            .temporary()
+            .get(&CommitmentsDataKeys::Commitment(commitment.clone()))
+    }
+
+    fn remove_commitment(&self, commitment: &BytesN<32>) {
+        self.storage()
+            .temporary()
+            .remove(&CommitmentsDataKeys::Commitment(commitment.clone()));
+    }
+}
@@ -1,8 +1,9 @@
-use soroban_sdk::{contracttype, Address, Bytes, Env, Vec};
+use crate::errors::ContractErrors;
+use soroban_sdk::{contracttype, panic_with_error, Address, Bytes, Env, String, Vec};
 
 #[contracttype]
 pub struct CoreData {
-    // Admin can upgrade the contract and liquidate nodes
+    // Admin can upgrade the contract and grant roles, expired nodes can be liquidated by anyone (see `liquidate`)
     pub adm: Address,
 
     // @deprecated - We don't use this anymore
@@ -25,11 +26,136 @@ pub struct OffersConfig {
     pub fee: u128,
 }
 
+// The grace period can't be longer than a year
+pub const MAX_GRACE_PERIOD: u64 = 3600 * 24 * 365;
+
+#[contracttype]
+pub struct ExpirationConfig {
+    // Amount of time (in seconds) after the expiration date where only the owner of the domain can renew it.
+    // Once this period is over, anyone can register the domain again.
+    pub grace_period: u64,
+
+    // Percentage of the expired domain collateral (100_0000000 is 100%) that goes to the `fee_taker` when the domain is
+    // registered again, the rest of the collateral goes back to the old owner.
+    pub protocol_share: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollateralConfig {
+    // Min percentage (100_0000000 is 100%) of the current collateral price a domain needs to keep locked. Domains below
+    // it can't be transferred until their collateral is topped up with `rebalance_collateral`, when they are sold the
+    // difference is taken from the amount of the offer. Zero disables the check.
+    pub min_ratio: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LiquidationConfig {
+    // Percentage of the collateral (100_0000000 is 100%) paid to the address that liquidates a domain once its grace
+    // period is over. It's taken after the `protocol_share` of the expiration config.
+    pub keeper_reward: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DurationConfig {
+    // The max amount of time (in seconds) a domain can be registered for, counting from now. It also limits renewals so
+    // the expiration date can't be more than `max_duration` in the future. Zero means there is no limit.
+    pub max_duration: u64,
+
+    // Percentage of the collateral (100_0000000 is 100%) paid every year of registration, it's prorated by the duration
+    // and sent to the `fee_taker`. Unlike the collateral, this fee is not returned when the domain is burned.
+    pub annual_fee: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct OracleConfig {
+    // Max age (in seconds) of the oracle price, older prices are rejected. Zero disables the check.
+    pub max_age: u64,
+
+    // Amount of records used to calculate the average price, it uses the history of the oracle (one record every
+    // resolution period). Zero or one means we only use the last price.
+    pub twap_records: u32,
+
+    // Oracle used when the main oracle doesn't have a valid price
+    pub fallback: Option<Address>,
+
+    // Min and max price (with 7 decimals) of the collateral, prices out of these bounds are rejected.
+    // A zero `max_price` means there is no upper bound.
+    pub min_price: u128,
+    pub max_price: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommitmentsConfig {
+    // If true, domains can only be registered with a commitment (`commit` and then `reveal_and_register`) and
+    // `set_record` is disabled
+    pub required: bool,
+
+    // Min amount of ledgers between the commitment and the reveal, so the commitment can't be made in the same
+    // transaction (or ledger) where the name is revealed
+    pub min_delay: u32,
+
+    // Max amount of ledgers a commitment can be revealed after it was made, older commitments are removed
+    pub max_age: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct NftConfig {
+    // Name and symbol of the collection, wallets and marketplaces show them next to the domains
+    pub name: String,
+    pub symbol: String,
+
+    // The URI of a domain is this value followed by its node in hex, for example `https://example.com/nft/2fe4...`.
+    // An empty value means the domains don't have a URI.
+    pub base_uri: String,
+}
+
+#[contracttype]
+pub struct SubdomainsConfig {
+    // The max amount of levels a subdomain can have, for example `a.b.example.xlm` has a depth of 2.
+    // This bounds the cost of validating a subdomain because we need to check every parent until the root domain.
+    pub max_depth: u32,
+
+    // If true, the controller of the parent can remove the owner of a subdomain and take back the control of it
+    pub parent_can_revoke: bool,
+}
+
+// Scopes of the pause switch, pausing `All` blocks every scope.
+// Burning records and offers is never paused so users can always withdraw their funds.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PauseScope {
+    // Setting, renewing, transferring and updating domains
+    Registration,
+
+    // Setting and taking offers
+    Marketplace,
+
+    // Setting, transferring and updating subdomains
+    Subdomains,
+
+    All,
+}
+
 #[contracttype]
 pub enum CoreDataKeys {
     CoreData,
     OffersConfig,
     Oracle,
+    ExpirationConfig,
+    LiquidationConfig,
+    CollateralConfig,
+    SubdomainsConfig,
+    OracleConfig,
+    DurationConfig,
+    CommitmentsConfig,
+    NftConfig,
+    Paused(PauseScope),
 }
 
 pub trait CoreDataEntity {
@@ -40,6 +166,25 @@ pub trait CoreDataEntity {
     fn oracle(&self) -> Address;
     fn set_offers_config(&self, v: &OffersConfig);
     fn offers_config(&self) -> Option<OffersConfig>;
+    fn set_expiration_config(&self, v: &ExpirationConfig);
+    fn expiration_config(&self) -> ExpirationConfig;
+    fn set_liquidation_config(&self, v: &LiquidationConfig);
+    fn liquidation_config(&self) -> LiquidationConfig;
+    fn set_collateral_config(&self, v: &CollateralConfig);
+    fn collateral_config(&self) -> CollateralConfig;
+    fn set_subdomains_config(&self, v: &SubdomainsConfig);
+    fn set_oracle_config(&self, v: &OracleConfig);
+    fn oracle_config(&self) -> OracleConfig;
+    fn subdomains_config(&self) -> SubdomainsConfig;
+    fn set_duration_config(&self, v: &DurationConfig);
+    fn duration_config(&self) -> DurationConfig;
+    fn set_commitments_config(&self, v: &CommitmentsConfig);
+    fn commitments_config(&self) -> CommitmentsConfig;
+    fn set_nft_config(&self, v: &NftConfig);
+    fn nft_config(&self) -> NftConfig;
+    fn set_paused(&self, scope: &PauseScope, paused: bool);
+    fn is_paused(&self, scope: &PauseScope) -> bool;
+    fn require_not_paused(&self, scope: &PauseScope);
     fn is_adm(&self);
 }
 
@@ -80,6 +225,166 @@ impl CoreDataEntity for Env {
         self.storage().instance().get(&CoreDataKeys::OffersConfig)
     }
 
+    fn set_expiration_config(&self, v: &ExpirationConfig) {
+        self.storage()
+            .instance()
+            .set(&CoreDataKeys::ExpirationConfig, v);
+    }
+
+    // If the admin hasn't set a config yet, domains have a grace period of 30 days and the whole collateral goes back to
+    // the old owner
+    fn expiration_config(&self) -> ExpirationConfig {
+        self.storage()
+            .instance()
+            .get(&CoreDataKeys::ExpirationConfig)
+            .unwrap_or(ExpirationConfig {
+                grace_period: 3600 * 24 * 30,
+                protocol_share: 0,
+            })
+    }
+
+    fn set_liquidation_config(&self, v: &LiquidationConfig) {
+        self.storage()
+            .instance()
+            .set(&CoreDataKeys::LiquidationConfig, v);
+    }
+
+    // If the admin hasn't set a config yet, the keeper gets 5% of the collateral
+    fn liquidation_config(&self) -> LiquidationConfig {
+        self.storage()
+            .instance()
+            .get(&CoreDataKeys::LiquidationConfig)
+            .unwrap_or(LiquidationConfig {
+                keeper_reward: 5_0000000,
+            })
+    }
+
+    fn set_collateral_config(&self, v: &CollateralConfig) {
+        self.storage()
+            .instance()
+            .set(&CoreDataKeys::CollateralConfig, v);
+    }
+
+    // If the admin hasn't set a config yet, domains keep the collateral they locked when they were registered or renewed
+    fn collateral_config(&self) -> CollateralConfig {
+        self.storage()
+            .instance()
+            .get(&CoreDataKeys::CollateralConfig)
+            .unwrap_or(CollateralConfig { min_ratio: 0 })
+    }
+
+    fn set_subdomains_config(&self, v: &SubdomainsConfig) {
+        self.storage()
+            .instance()
+            .set(&CoreDataKeys::SubdomainsConfig, v);
+    }
+
+    fn subdomains_config(&self) -> SubdomainsConfig {
+        self.storage()
+            .instance()
+            .get(&CoreDataKeys::SubdomainsConfig)
+            .unwrap_or(SubdomainsConfig {
+                max_depth: 5,
+                parent_can_revoke: true,
+            })
+    }
+
+    fn set_oracle_config(&self, v: &OracleConfig) {
+        self.storage()
+            .instance()
+            .set(&CoreDataKeys::OracleConfig, v);
+    }
+
+    // If the admin hasn't set a config yet, prices older than 15 minutes (3 reflector periods) are rejected
+    fn oracle_config(&self) -> OracleConfig {
+        self.storage()
+            .instance()
+            .get(&CoreDataKeys::OracleConfig)
+            .unwrap_or(OracleConfig {
+                max_age: 900,
+                twap_records: 0,
+                fallback: None,
+                min_price: 0,
+                max_price: 0,
+            })
+    }
+
+    fn set_duration_config(&self, v: &DurationConfig) {
+        self.storage()
+            .instance()
+            .set(&CoreDataKeys::DurationConfig, v);
+    }
+
+    // If the admin hasn't set a config yet, domains can be registered for any duration and there is no annual fee
+    fn duration_config(&self) -> DurationConfig {
+        self.storage()
+            .instance()
+            .get(&CoreDataKeys::DurationConfig)
+            .unwrap_or(DurationConfig {
+                max_duration: 0,
+                annual_fee: 0,
+            })
+    }
+
+    fn set_commitments_config(&self, v: &CommitmentsConfig) {
+        self.storage()
+            .instance()
+            .set(&CoreDataKeys::CommitmentsConfig, v);
+    }
+
+    // If the admin hasn't set a config yet, `set_record` can still be used and commitments can be revealed between 5
+    // ledgers and one day (17280 ledgers) after they were made
+    fn commitments_config(&self) -> CommitmentsConfig {
+        self.storage()
+            .instance()
+            .get(&CoreDataKeys::CommitmentsConfig)
+            .unwrap_or(CommitmentsConfig {
+                required: false,
+                min_delay: 5,
+                max_age: 17280,
+            })
+    }
+
+    fn set_nft_config(&self, v: &NftConfig) {
+        self.storage().instance().set(&CoreDataKeys::NftConfig, v);
+    }
+
+    fn nft_config(&self) -> NftConfig {
+        self.storage()
+            .instance()
+            .get(&CoreDataKeys::NftConfig)
+            .unwrap_or_else(|| NftConfig {
+                name: String::from_str(&self, "Soroban Domains"),
+                symbol: String::from_str(&self, "DOMAIN"),
+                base_uri: String::from_str(&self, ""),
+            })
+    }
+
+    fn set_paused(&self, scope: &PauseScope, paused: bool) {
+        let key: CoreDataKeys = CoreDataKeys::Paused(scope.clone());
+        if paused {
+            self.storage().instance().set(&key, &true);
+        } else {
+            self.storage().instance().remove(&key);
+        }
+    }
+
+    fn is_paused(&self, scope: &PauseScope) -> bool {
+        self.storage()
+            .instance()
+            .has(&CoreDataKeys::Paused(scope.clone()))
+            || self
+                .storage()
+                .instance()
+                .has(&CoreDataKeys::Paused(PauseScope::All))
+    }
+
+    fn require_not_paused(&self, scope: &PauseScope) {
+        if self.is_paused(scope) {
+            panic_with_error!(&self, &ContractErrors::ContractPaused);
+        }
+    }
+
     fn is_adm(&self) {
         self.core_data().unwrap().adm.require_auth();
     }
@@ -1,3 +1,10 @@
+pub mod approvals;
+pub mod commitments;
 pub mod core;
 pub mod offers;
+pub mod owners;
+pub mod pricing;
 pub mod record;
+pub mod roles;
+pub mod subdomains;
+pub mod tlds;
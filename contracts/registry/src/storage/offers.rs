@@ -35,6 +35,7 @@ pub enum Offer {
 }
 
 #[contracttype]
+#[derive(Clone, Debug, PartialEq)]
 pub enum OffersDataKeys {
     BuyOffer(BytesN<32>),
     SaleOffer(BytesN<32>),
@@ -0,0 +1,114 @@
+use soroban_sdk::{contracttype, Address, BytesN, Env, IntoVal, Val};
+
+// Max amount of domains returned by a page of `domains_of`
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+// The domains of an owner are saved as a list: `Balance` is the length of the list, `Domain` is the node at a position
+// and `Position` is the position of a node in the list of its owner (a node has only one owner). Removing a domain
+// moves the last domain of the list into its position, so the order of the list can change.
+#[contracttype]
+pub enum OwnersDataKeys {
+    // Persistent keys
+    Balance(Address),
+    Domain((Address, u32)),
+    Position(BytesN<32>),
+}
+
+pub trait OwnersEntity {
+    fn add_owned_domain(&self, owner: &Address, node: &BytesN<32>);
+    fn remove_owned_domain(&self, owner: &Address, node: &BytesN<32>);
+    fn owned_domain(&self, owner: &Address, position: u32) -> Option<BytesN<32>>;
+    fn domains_balance(&self, owner: &Address) -> u32;
+    fn bump_owned_domain(&self, owner: &Address, node: &BytesN<32>);
+}
+
+// Every entry of the index is bumped when it's updated
+fn set_and_bump<V: IntoVal<Env, Val>>(e: &Env, key: &OwnersDataKeys, value: &V) {
+    e.storage().persistent().set(key, value);
+    e.storage()
+        .persistent()
+        .extend_ttl(key, 17280, e.ledger().sequence() + (17280 * 30));
+}
+
+impl OwnersEntity for Env {
+    fn add_owned_domain(&self, owner: &Address, node: &BytesN<32>) {
+        let balance: u32 = self.domains_balance(owner);
+        set_and_bump(
+            self,
+            &OwnersDataKeys::Domain((owner.clone(), balance)),
+            node,
+        );
+        set_and_bump(self, &OwnersDataKeys::Position(node.clone()), &balance);
+        set_and_bump(
+            self,
+            &OwnersDataKeys::Balance(owner.clone()),
+            &(balance + 1),
+        );
+    }
+
+    // Domains registered before we started indexing the owners are not in the list, so there is nothing to remove
+    fn remove_owned_domain(&self, owner: &Address, node: &BytesN<32>) {
+        let position_key: OwnersDataKeys = OwnersDataKeys::Position(node.clone());
+        let position: u32 = match self.storage().persistent().get(&position_key) {
+            Some(position) => position,
+            None => return,
+        };
+
+        let last: u32 = self.domains_balance(owner) - 1;
+        if position != last {
+            let last_node: BytesN<32> = self.owned_domain(owner, last).unwrap();
+            set_and_bump(
+                self,
+                &OwnersDataKeys::Domain((owner.clone(), position)),
+                &last_node,
+            );
+            set_and_bump(self, &OwnersDataKeys::Position(last_node), &position);
+        }
+
+        self.storage()
+            .persistent()
+            .remove(&OwnersDataKeys::Domain((owner.clone(), last)));
+        self.storage().persistent().remove(&position_key);
+
+        let balance_key: OwnersDataKeys = OwnersDataKeys::Balance(owner.clone());
+        if last == 0 {
+            self.storage().persistent().remove(&balance_key);
+        } else {
+            set_and_bump(self, &balance_key, &last);
+        }
+    }
+
+    fn owned_domain(&self, owner: &Address, position: u32) -> Option<BytesN<32>> {
+        self.storage()
+            .persistent()
+            .get(&OwnersDataKeys::Domain((owner.clone(), position)))
+    }
+
+    fn domains_balance(&self, owner: &Address) -> u32 {
+        self.storage()
+            .persistent()
+            .get(&OwnersDataKeys::Balance(owner.clone()))
+            .unwrap_or(0)
+    }
+
+    // The entries of a domain in the index live as long as the record, it's called every time the record is bumped
+    fn bump_owned_domain(&self, owner: &Address, node: &BytesN<32>) {
+        let position_key: OwnersDataKeys = OwnersDataKeys::Position(node.clone());
+        let position: u32 = match self.storage().persistent().get(&position_key) {
+            Some(position) => position,
+            None => return,
+        };
+
+        for key in [
+            position_key,
+            OwnersDataKeys::Domain((owner.clone(), position)),
+            OwnersDataKeys::Balance(owner.clone()),
+        ] {
+            self.storage().persistent().extend_ttl(
+                &key,
+                17280,
+                self.ledger().sequence() + (17280 * 30),
+            );
+        }
+    }
+}
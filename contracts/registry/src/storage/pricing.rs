@@ -0,0 +1,85 @@
+use crate::storage::tlds::TldsEntity;
+use soroban_sdk::{contracttype, vec, Bytes, BytesN, Env, Vec};
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PricingTable {
+    // USD prices (with 7 decimals) by the length of the domain: the first value is the price of domains with 1
+    // character, the second one of domains with 2 characters and so on.
+    // Domains longer than the table use the last value.
+    pub length_prices: Vec<u128>,
+}
+
+#[contracttype]
+pub enum PricingDataKeys {
+    // Instance keys
+    Default,
+
+    // Persistent keys
+    Premium(BytesN<32>),
+}
+
+pub trait PricingEntity {
+    fn set_default_pricing_table(&self, table: &Option<PricingTable>);
+    fn pricing_table(&self, tld: &Option<Bytes>) -> PricingTable;
+    fn set_premium_price(&self, node: &BytesN<32>, price: &Option<u128>);
+    fn premium_price(&self, node: &BytesN<32>) -> Option<u128>;
+}
+
+impl PricingEntity for Env {
+    // Setting a `None` table resets the default table to the prices we had before the table was introduced
+    fn set_default_pricing_table(&self, table: &Option<PricingTable>) {
+        match table {
+            Some(table) => self
+                .storage()
+                .instance()
+                .set(&PricingDataKeys::Default, table),
+            None => self.storage().instance().remove(&PricingDataKeys::Default),
+        }
+    }
+
+    // The table of a TLD is part of its config, if the TLD has an empty table we use the default one.
+    // If the admin hasn't set a default table then we use the prices we had before the table was introduced
+    fn pricing_table(&self, tld: &Option<Bytes>) -> PricingTable {
+        if let Some(config) = tld.as_ref().and_then(|tld| self.tld_config(tld)) {
+            if !config.pricing_table.length_prices.is_empty() {
+                return config.pricing_table;
+            }
+        }
+
+        self.storage()
+            .instance()
+            .get(&PricingDataKeys::Default)
+            .unwrap_or(PricingTable {
+                length_prices: vec![
+                    &self,
+                    187_5700000,
+                    107_1800000,
+                    61_2500000,
+                    35_0000000,
+                    20_0000000,
+                ],
+            })
+    }
+
+    fn set_premium_price(&self, node: &BytesN<32>, price: &Option<u128>) {
+        let key: PricingDataKeys = PricingDataKeys::Premium(node.clone());
+        match price {
+            Some(price) => {
+                self.storage().persistent().set(&key, price);
+                self.storage().persistent().extend_ttl(
+                    &key,
+                    17280,
+                    self.ledger().sequence() + (17280 * 30),
+                );
+            }
+            None => self.storage().persistent().remove(&key),
+        }
+    }
+
+    fn premium_price(&self, node: &BytesN<32>) -> Option<u128> {
+        self.storage()
+            .persistent()
+            .get(&PricingDataKeys::Premium(node.clone()))
+    }
+}
@@ -1,4 +1,6 @@
-use soroban_sdk::{contracttype, Address, BytesN, Env};
+use crate::storage::owners::OwnersEntity;
+use crate::storage::subdomains::SubdomainsEntity;
+use soroban_sdk::{contracttype, Address, Bytes, BytesN, Env};
 
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
@@ -13,7 +15,8 @@ pub struct Domain {
     pub address: Address,
 
     // The TTL is the end expiration date of the domain.
-    // A domain that have been expired for at least 30 days can be claimed by another address
+    // A domain that have been expired for longer than the grace period (30 days by default) can be claimed by another
+    // address
     pub exp_date: u64,
 
     // The collateral is the amount of reserves the owner of the domain has deposited
@@ -32,14 +35,14 @@ pub struct SubDomain {
     // The node is the hash of the subdomain
     pub node: BytesN<32>,
 
-    // Parent is the hash of the root of the domain
+    // Parent is the node of the parent record, it can be either the root domain or another subdomain
     pub parent: BytesN<32>,
 
     // The address is where the node resolves to
     pub address: Address,
 
-    // The snapshot is taken from the parent domain
-    // If the subdomain snapshot is different from the parent one, it means the subdomain is invalid
+    // The snapshot is taken from the root domain
+    // If the subdomain snapshot is different from the root one, it means the subdomain is invalid
     pub snapshot: u64,
 }
 
@@ -56,11 +59,41 @@ pub enum RecordKeys {
     SubRecord(BytesN<32>),
 }
 
+// The human-readable name of a root domain, it's saved next to the record because the node is just a hash, and we need
+// the name in order to price the domain again (for example when it's renewed).
+// Domains registered before this value was introduced don't have it.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordName {
+    pub domain: Bytes,
+    pub tld: Bytes,
+}
+
+#[contracttype]
+pub enum RecordNameKeys {
+    Name(BytesN<32>),
+}
+
+// Subdomains can have their own owner, if they don't have one they are managed by the controller of their parent.
+// It's saved next to the record so the `SubDomain` struct stays the same for subdomains created before this value.
+#[contracttype]
+pub enum SubOwnerKeys {
+    Owner(BytesN<32>),
+}
+
 pub trait RecordEntity {
     fn set_record(&self, record: &Record);
     fn record(&self, node: &RecordKeys) -> Option<Record>;
     fn bump_record(&self, record_type: &RecordKeys);
     fn burn_record(&self, record_type: &RecordKeys);
+    fn set_record_name(&self, node: &BytesN<32>, name: &RecordName);
+    fn record_name(&self, node: &BytesN<32>) -> Option<RecordName>;
+    fn bump_record_name(&self, node: &BytesN<32>);
+    fn burn_record_name(&self, node: &BytesN<32>);
+    fn set_sub_owner(&self, node: &BytesN<32>, owner: &Address);
+    fn sub_owner(&self, node: &BytesN<32>) -> Option<Address>;
+    fn bump_sub_owner(&self, node: &BytesN<32>);
+    fn burn_sub_owner(&self, node: &BytesN<32>);
 }
 
 impl RecordEntity for Env {
@@ -83,9 +116,87 @@ impl RecordEntity for Env {
             17280,
             self.ledger().sequence() + (17280 * 30),
         );
+
+        // The name, the owner and the indexes live as long as the record, so we keep the entries alive together
+        match record_type {
+            RecordKeys::Record(node) => {
+                self.bump_record_name(node);
+                if let Some(Record::Domain(domain)) = self.record(record_type) {
+                    self.bump_owned_domain(&domain.owner, node);
+                }
+            }
+            RecordKeys::SubRecord(node) => {
+                self.bump_sub_owner(node);
+                if let Some(Record::SubDomain(sub)) = self.record(record_type) {
+                    self.bump_subdomain(&sub.parent, node);
+                }
+            }
+        }
     }
 
     fn burn_record(&self, record_type: &RecordKeys) {
         self.storage().persistent().remove(record_type)
     }
+
+    fn set_record_name(&self, node: &BytesN<32>, name: &RecordName) {
+        self.storage()
+            .persistent()
+            .set(&RecordNameKeys::Name(node.clone()), name);
+    }
+
+    fn record_name(&self, node: &BytesN<32>) -> Option<RecordName> {
+        self.storage()
+            .persistent()
+            .get(&RecordNameKeys::Name(node.clone()))
+    }
+
+    fn bump_record_name(&self, node: &BytesN<32>) {
+        let key: RecordNameKeys = RecordNameKeys::Name(node.clone());
+        if !self.storage().persistent().has(&key) {
+            return;
+        }
+
+        self.storage().persistent().extend_ttl(
+            &key,
+            17280,
+            self.ledger().sequence() + (17280 * 30),
+        );
+    }
+
+    fn burn_record_name(&self, node: &BytesN<32>) {
+        self.storage()
+            .persistent()
+            .remove(&RecordNameKeys::Name(node.clone()))
+    }
+
+    fn set_sub_owner(&self, node: &BytesN<32>, owner: &Address) {
+        self.storage()
+            .persistent()
+            .set(&SubOwnerKeys::Owner(node.clone()), owner);
+    }
+
+    fn sub_owner(&self, node: &BytesN<32>) -> Option<Address> {
+        self.storage()
+            .persistent()
+            .get(&SubOwnerKeys::Owner(node.clone()))
+    }
+
+    fn bump_sub_owner(&self, node: &BytesN<32>) {
+        let key: SubOwnerKeys = SubOwnerKeys::Owner(node.clone());
+        if !self.storage().persistent().has(&key) {
+            return;
+        }
+
+        self.storage().persistent().extend_ttl(
+            &key,
+            17280,
+            self.ledger().sequence() + (17280 * 30),
+        );
+    }
+
+    fn burn_sub_owner(&self, node: &BytesN<32>) {
+        self.storage()
+            .persistent()
+            .remove(&SubOwnerKeys::Owner(node.clone()))
+    }
 }
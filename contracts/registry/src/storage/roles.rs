@@ -0,0 +1,64 @@
+use crate::errors::ContractErrors;
+use crate::storage::core::CoreDataEntity;
+use soroban_sdk::{contracttype, panic_with_error, Address, Env};
+
+// Roles are used to split the admin powers so routine operations don't need the admin key.
+// The admin is the super role: it can grant and revoke roles and it can call every function that requires a role.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Role {
+    // Can upgrade the contract wasm
+    Upgrader,
+
+    // Can create, update and retire TLDs, and set the commitments config
+    TldManager,
+
+    // Can update the oracle used to price the domains
+    PricingManager,
+
+    // Can update the fees of the offers and the expiration config
+    FeeManager,
+
+    // Can pause the contract
+    Pauser,
+}
+
+#[contracttype]
+pub enum RolesDataKeys {
+    Role((Role, Address)),
+}
+
+pub trait RolesEntity {
+    fn grant_role(&self, role: &Role, account: &Address);
+    fn revoke_role(&self, role: &Role, account: &Address);
+    fn has_role(&self, role: &Role, account: &Address) -> bool;
+    fn require_role(&self, role: &Role, caller: &Address);
+}
+
+impl RolesEntity for Env {
+    fn grant_role(&self, role: &Role, account: &Address) {
+        self.storage()
+            .instance()
+            .set(&RolesDataKeys::Role((role.clone(), account.clone())), &true);
+    }
+
+    fn revoke_role(&self, role: &Role, account: &Address) {
+        self.storage()
+            .instance()
+            .remove(&RolesDataKeys::Role((role.clone(), account.clone())));
+    }
+
+    fn has_role(&self, role: &Role, account: &Address) -> bool {
+        self.storage()
+            .instance()
+            .has(&RolesDataKeys::Role((role.clone(), account.clone())))
+    }
+
+    fn require_role(&self, role: &Role, caller: &Address) {
+        caller.require_auth();
+
+        if self.core_data().unwrap().adm != *caller && !self.has_role(role, caller) {
+            panic_with_error!(&self, &ContractErrors::NotAuthorized);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use soroban_sdk::{contracttype, BytesN, Env, IntoVal, Val};
+
+// The subdomains of a parent (a domain or another subdomain) are saved as a list, like the domains of an owner: `Count`
+// is the length of the list, `Subdomain` is the node at a position and `SubPosition` is the position of a subdomain in
+// the list of its parent. Removing a subdomain moves the last subdomain of the list into its position.
+// Keys are saved without the name of the enum, so a variant can't share its name with a variant of another enum that
+// has the same value (like `OwnersDataKeys::Position`).
+#[contracttype]
+pub enum SubdomainsDataKeys {
+    // Persistent keys
+    Count(BytesN<32>),
+    Subdomain((BytesN<32>, u32)),
+    SubPosition(BytesN<32>),
+}
+
+pub trait SubdomainsEntity {
+    fn add_subdomain(&self, parent: &BytesN<32>, node: &BytesN<32>);
+    fn remove_subdomain(&self, parent: &BytesN<32>, node: &BytesN<32>);
+    fn subdomain(&self, parent: &BytesN<32>, position: u32) -> Option<BytesN<32>>;
+    fn subdomains_count(&self, parent: &BytesN<32>) -> u32;
+    fn bump_subdomain(&self, parent: &BytesN<32>, node: &BytesN<32>);
+}
+
+// Every entry of the index is bumped when it's updated
+fn set_and_bump<V: IntoVal<Env, Val>>(e: &Env, key: &SubdomainsDataKeys, value: &V) {
+    e.storage().persistent().set(key, value);
+    e.storage()
+        .persistent()
+        .extend_ttl(key, 17280, e.ledger().sequence() + (17280 * 30));
+}
+
+impl SubdomainsEntity for Env {
+    // Subdomains that are set again (for example after they were outdated) keep their position
+    fn add_subdomain(&self, parent: &BytesN<32>, node: &BytesN<32>) {
+        let position_key: SubdomainsDataKeys = SubdomainsDataKeys::SubPosition(node.clone());
+        if self.storage().persistent().has(&position_key) {
+            return;
+        }
+
+        let count: u32 = self.subdomains_count(parent);
+        set_and_bump(
+            self,
+            &SubdomainsDataKeys::Subdomain((parent.clone(), count)),
+            node,
+        );
+        set_and_bump(self, &position_key, &count);
+        set_and_bump(
+            self,
+            &SubdomainsDataKeys::Count(parent.clone()),
+            &(count + 1),
+        );
+    }
+
+    // Subdomains created before we started indexing them are not in the list, so there is nothing to remove
+    fn remove_subdomain(&self, parent: &BytesN<32>, node: &BytesN<32>) {
+        let position_key: SubdomainsDataKeys = SubdomainsDataKeys::SubPosition(node.clone());
+        let position: u32 = match self.storage().persistent().get(&position_key) {
+            Some(position) => position,
+            None => return,
+        };
+
+        let last: u32 = self.subdomains_count(parent) - 1;
+        if position != last {
+            let last_node: BytesN<32> = self.subdomain(parent, last).unwrap();
+            set_and_bump(
+                self,
+                &SubdomainsDataKeys::Subdomain((parent.clone(), position)),
+                &last_node,
+            );
+            set_and_bump(self, &SubdomainsDataKeys::SubPosition(last_node), &position);
+        }
+
+        self.storage()
+            .persistent()
+            .remove(&SubdomainsDataKeys::Subdomain((parent.clone(), last)));
+        self.storage().persistent().remove(&position_key);
+
+        let count_key: SubdomainsDataKeys = SubdomainsDataKeys::Count(parent.clone());
+        if last == 0 {
+            self.storage().persistent().remove(&count_key);
+        } else {
+            set_and_bump(self, &count_key, &last);
+        }
+    }
+
+    fn subdomain(&self, parent: &BytesN<32>, position: u32) -> Option<BytesN<32>> {
+        self.storage()
+            .persistent()
+            .get(&SubdomainsDataKeys::Subdomain((parent.clone(), position)))
+    }
+
+    fn subdomains_count(&self, parent: &BytesN<32>) -> u32 {
+        self.storage()
+            .persistent()
+            .get(&SubdomainsDataKeys::Count(parent.clone()))
+            .unwrap_or(0)
+    }
+
+    // The entries of a subdomain in the index live as long as the record, it's called every time the record is bumped
+    fn bump_subdomain(&self, parent: &BytesN<32>, node: &BytesN<32>) {
+        let position_key: SubdomainsDataKeys = SubdomainsDataKeys::SubPosition(node.clone());
+        let position: u32 = match self.storage().persistent().get(&position_key) {
+            Some(position) => position,
+            None => return,
+        };
+
+        for key in [
+            position_key,
+            SubdomainsDataKeys::Subdomain((parent.clone(), position)),
+            SubdomainsDataKeys::Count(parent.clone()),
+        ] {
+            self.storage().persistent().extend_ttl(
+                &key,
+                17280,
+                self.ledger().sequence() + (17280 * 30),
+            );
+        }
+    }
+}
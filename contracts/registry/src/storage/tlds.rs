@@ -0,0 +1,160 @@
+use crate::storage::core::{CoreData, CoreDataEntity};
+use crate::storage::pricing::PricingTable;
+use soroban_sdk::{contracttype, Address, Bytes, BytesN, Env, Vec};
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct NameRules {
+    // Min and max amount of characters of the domains
+    pub min_length: u32,
+    pub max_length: u32,
+
+    // Lowercase letters (a-z) are always allowed, digits (0-9) and hyphens are optional.
+    // Hyphens can't be at the start or the end of the name and there can't be two hyphens in a row.
+    pub allow_digits: bool,
+    pub allow_hyphens: bool,
+
+    // Allows internationalized names in their punycode form (`xn--...`), see `utils::idn`
+    pub allow_idn: bool,
+}
+
+// The rules we had before TLDs had their own config
+impl Default for NameRules {
+    fn default() -> Self {
+        NameRules {
+            min_length: 1,
+            max_length: 15,
+            allow_digits: false,
+            allow_hyphens: false,
+            allow_idn: false,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TldConfig {
+    // The address that manages the TLD, for example a business that has its own TLD
+    pub operator: Option<Address>,
+
+    // If false, only the operator can register domains (the operator needs to sign `set_record`)
+    pub public: bool,
+
+    // The min amount of time a domain of this TLD can be registered
+    pub min_duration: u64,
+
+    // If the table is empty, the domains of this TLD use the default pricing table
+    pub pricing_table: PricingTable,
+
+    pub name_rules: NameRules,
+
+    // Percentage of the protocol fees paid by the domains of this TLD (100_0000000 is 100%) that goes to the operator
+    // instead of the `fee_taker`
+    pub revenue_share: u128,
+}
+
+// Lifecycle of a TLD, each status defines what happens to the domains of the TLD:
+// - `Active`: domains can be registered, renewed and traded.
+// - `RegistrationClosed`: new domains can't be registered (expired domains can't be registered again either), existing
+//   domains can still be renewed and traded.
+// - `Sunset(date)`: like `RegistrationClosed` but domains can't be renewed beyond the sunset date and offers can't be
+//   set or taken (buyers can still cancel their offers to get the amount back).
+// In every status, existing domains keep resolving until their expiration date so domains registered before the TLD was
+// closed keep their whole term.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum TldStatus {
+    Active,
+    RegistrationClosed,
+    Sunset(u64),
+}
+
+#[contracttype]
+pub enum TldsDataKeys {
+    // Instance keys
+    Tld(Bytes),
+    Status(Bytes),
+
+    // Persistent keys
+    Blocked(BytesN<32>),
+}
+
+pub trait TldsEntity {
+    fn set_tld_config(&self, tld: &Bytes, config: &TldConfig);
+    fn tld_config(&self, tld: &Bytes) -> Option<TldConfig>;
+    fn set_tld_status(&self, tld: &Bytes, status: &TldStatus);
+    fn tld_status(&self, tld: &Bytes) -> TldStatus;
+    fn set_blocked(&self, node: &BytesN<32>, blocked: bool);
+    fn is_blocked(&self, node: &BytesN<32>) -> bool;
+}
+
+impl TldsEntity for Env {
+    fn set_tld_config(&self, tld: &Bytes, config: &TldConfig) {
+        self.storage()
+            .instance()
+            .set(&TldsDataKeys::Tld(tld.clone()), config);
+    }
+
+    fn set_tld_status(&self, tld: &Bytes, status: &TldStatus) {
+        let key: TldsDataKeys = TldsDataKeys::Status(tld.clone());
+        match status {
+            TldStatus::Active => self.storage().instance().remove(&key),
+            _ => self.storage().instance().set(&key, status),
+        }
+    }
+
+    fn tld_status(&self, tld: &Bytes) -> TldStatus {
+        self.storage()
+            .instance()
+            .get(&TldsDataKeys::Status(tld.clone()))
+            .unwrap_or(TldStatus::Active)
+    }
+
+    fn set_blocked(&self, node: &BytesN<32>, blocked: bool) {
+        let key: TldsDataKeys = TldsDataKeys::Blocked(node.clone());
+        if blocked {
+            self.storage().persistent().set(&key, &true);
+            self.storage().persistent().extend_ttl(
+                &key,
+                17280,
+                self.ledger().sequence() + (17280 * 30),
+            );
+        } else {
+            self.storage().persistent().remove(&key);
+        }
+    }
+
+    fn is_blocked(&self, node: &BytesN<32>) -> bool {
+        self.storage()
+            .persistent()
+            .has(&TldsDataKeys::Blocked(node.clone()))
+    }
+
+    // TLDs added before the TLDs had their own config are only in the `allowed_tlds` list, they are public and they
+    // use the min duration of the core data and the default pricing table and rules
+    fn tld_config(&self, tld: &Bytes) -> Option<TldConfig> {
+        if let Some(config) = self
+            .storage()
+            .instance()
+            .get(&TldsDataKeys::Tld(tld.clone()))
+        {
+            return Some(config);
+        }
+
+        let core_data: CoreData = self.core_data()?;
+        if !core_data.allowed_tlds.contains(tld.clone()) {
+            return None;
+        }
+
+        Some(TldConfig {
+            operator: None,
+            public: true,
+            min_duration: core_data.min_duration,
+            pricing_table: PricingTable {
+                length_prices: Vec::new(&self),
+            },
+            name_rules: NameRules::default(),
+            revenue_share: 0,
+        })
+    }
+}
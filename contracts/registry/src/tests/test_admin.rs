@@ -1,9 +1,36 @@
 #![cfg(test)]
 
-use crate::storage::core::{CoreData, CoreDataEntity};
+use crate::errors::ContractErrors;
+use crate::storage::core::{CoreData, CoreDataEntity, PauseScope};
+use crate::storage::offers::OffersDataKeys;
+use crate::storage::pricing::PricingTable;
+use crate::storage::record::RecordKeys;
+use crate::storage::roles::Role;
+use crate::storage::tlds::{NameRules, TldConfig, TldStatus};
 use crate::tests::test_utils::{create_test_data, init_contract, TestData};
-use soroban_sdk::testutils::{MockAuth, MockAuthInvoke};
-use soroban_sdk::{Bytes, Env, IntoVal, Vec};
+use crate::utils::records::generate_node;
+use soroban_sdk::testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke};
+use soroban_sdk::{Address, Bytes, BytesN, Env, IntoVal, Vec};
+use test_utils::create_env;
+
+fn private_tld_config(e: &Env, operator: &Address) -> TldConfig {
+    TldConfig {
+        operator: Some(operator.clone()),
+        public: false,
+        min_duration: 3600 * 24 * 30,
+        pricing_table: PricingTable {
+            length_prices: Vec::from_array(&e, [50_0000000]),
+        },
+        name_rules: NameRules {
+            min_length: 3,
+            max_length: 30,
+            allow_digits: false,
+            allow_hyphens: false,
+            allow_idn: false,
+        },
+        revenue_share: 50_0000000,
+    }
+}
 
 #[test]
 pub fn test_updating_tlds() {
@@ -11,19 +38,32 @@ pub fn test_updating_tlds() {
     let test_data: TestData = create_test_data(&e);
     init_contract(&e, &test_data);
 
-    let core: CoreData = e.as_contract(&test_data.contract_client.address, || {
-        e.core_data().unwrap()
-    });
+    let operator: Address = Address::generate(&e);
+    let tld: Bytes = Bytes::from_slice(&e, "eth".as_bytes());
+    let config: TldConfig = private_tld_config(&e, &operator);
 
-    assert_eq!(core.allowed_tlds, test_data.allowed_tlds);
+    // TLDs from the allowed list use the legacy config
+    assert_eq!(
+        test_data
+            .contract_client
+            .tld_config(&Bytes::from_slice(&e, "xlm".as_bytes())),
+        Some(TldConfig {
+            operator: None,
+            public: true,
+            min_duration: test_data.min_duration,
+            pricing_table: PricingTable {
+                length_prices: Vec::new(&e),
+            },
+            name_rules: NameRules::default(),
+            revenue_share: 0,
+        })
+    );
+    assert_eq!(test_data.contract_client.tld_config(&tld), None);
 
     // It should fail because the admin didn't sign the transaction
     assert!(test_data
         .contract_client
-        .try_update_tlds(&Vec::from_array(
-            &e,
-            [Bytes::from_slice(&e, "eth".as_bytes())]
-        ))
+        .try_create_tld(&test_data.adm, &tld, &config)
         .is_err());
 
     test_data
@@ -32,26 +72,324 @@ pub fn test_updating_tlds() {
             address: &test_data.adm,
             invoke: &MockAuthInvoke {
                 contract: &test_data.contract_client.address,
-                fn_name: "update_tlds",
-                args: (Vec::from_array(
-                    &e,
-                    [Bytes::from_slice(&e, "eth".as_bytes())],
-                ),)
-                    .into_val(&e),
+                fn_name: "create_tld",
+                args: (test_data.adm.clone(), tld.clone(), config.clone()).into_val(&e),
                 sub_invokes: &[],
             },
         }])
-        .update_tlds(&Vec::from_array(
-            &e,
-            [Bytes::from_slice(&e, "eth".as_bytes())],
-        ));
+        .create_tld(&test_data.adm, &tld, &config);
 
     let updated_core: CoreData = e.as_contract(&test_data.contract_client.address, || {
         e.core_data().unwrap()
     });
+    let mut allowed_tlds: Vec<Bytes> = test_data.allowed_tlds.clone();
+    allowed_tlds.push_back(tld.clone());
+    assert_eq!(updated_core.allowed_tlds, allowed_tlds);
+    assert_eq!(
+        test_data.contract_client.tld_config(&tld),
+        Some(config.clone())
+    );
+
+    e.mock_all_auths();
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_create_tld(&test_data.adm, &tld, &config)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::TldAlreadyExists.into()
+    );
+
+    // Private TLDs need an operator
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_update_tld(
+                &test_data.adm,
+                &tld,
+                &TldConfig {
+                    operator: None,
+                    ..config.clone()
+                },
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidConfig.into()
+    );
+
+    let public_config: TldConfig = TldConfig {
+        public: true,
+        ..config.clone()
+    };
+    test_data
+        .contract_client
+        .update_tld(&test_data.adm, &tld, &public_config);
+    assert_eq!(
+        test_data.contract_client.tld_config(&tld),
+        Some(public_config)
+    );
+
+    // Only active TLDs are in the allowed list, closed TLDs keep their config
+    e.ledger().set_timestamp(100);
+    test_data
+        .contract_client
+        .set_tld_status(&test_data.adm, &tld, &TldStatus::RegistrationClosed);
+    let closed_core: CoreData = e.as_contract(&test_data.contract_client.address, || {
+        e.core_data().unwrap()
+    });
+    assert_eq!(closed_core.allowed_tlds, test_data.allowed_tlds);
+    assert_eq!(
+        test_data.contract_client.tld_status(&tld),
+        TldStatus::RegistrationClosed
+    );
+    assert!(test_data.contract_client.tld_config(&tld).is_some());
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_tld_status(&test_data.adm, &tld, &TldStatus::Sunset(99))
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidConfig.into()
+    );
+
+    test_data
+        .contract_client
+        .set_tld_status(&test_data.adm, &tld, &TldStatus::Active);
+    let active_core: CoreData = e.as_contract(&test_data.contract_client.address, || {
+        e.core_data().unwrap()
+    });
+    assert_eq!(active_core.allowed_tlds, allowed_tlds);
+}
+
+#[test]
+pub fn test_roles() {
+    let e: Env = Env::default();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let tld_manager: Address = Address::generate(&e);
+    let tld: Bytes = Bytes::from_slice(&e, "eth".as_bytes());
+    let config: TldConfig = private_tld_config(&e, &tld_manager);
+
+    assert!(!test_data
+        .contract_client
+        .has_role(&Role::TldManager, &tld_manager));
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_create_tld(&tld_manager, &tld, &config)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NotAuthorized.into()
+    );
+
+    // Only the admin can grant roles
+    test_data
+        .contract_client
+        .grant_role(&Role::TldManager, &tld_manager);
+    assert_eq!(e.auths().first().unwrap().0, test_data.adm);
+    assert!(test_data
+        .contract_client
+        .has_role(&Role::TldManager, &tld_manager));
+
+    test_data
+        .contract_client
+        .create_tld(&tld_manager, &tld, &config);
+    assert_eq!(e.auths().first().unwrap().0, tld_manager);
+
+    // A role doesn't give access to the functions of other roles
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_upgrade(&tld_manager, &BytesN::from_array(&e, &[0; 32]))
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NotAuthorized.into()
+    );
+
+    test_data
+        .contract_client
+        .revoke_role(&Role::TldManager, &tld_manager);
+    assert_eq!(e.auths().first().unwrap().0, test_data.adm);
+    assert!(!test_data
+        .contract_client
+        .has_role(&Role::TldManager, &tld_manager));
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_create_tld(&tld_manager, &tld, &config)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NotAuthorized.into()
+    );
+}
+
+#[test]
+pub fn test_pause() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let pauser: Address = Address::generate(&e);
+    let owner: Address = Address::generate(&e);
+    let buyer: Address = Address::generate(&e);
+    let domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    let node: BytesN<32> = generate_node(&e, &domain, &tld);
+
+    test_data.col_asset_stellar.mint(&owner, &i128::MAX);
+    test_data.col_asset_stellar.mint(&buyer, &1000_0000000);
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_pause(&pauser, &PauseScope::Marketplace)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NotAuthorized.into()
+    );
+
+    test_data.contract_client.grant_role(&Role::Pauser, &pauser);
+    test_data
+        .contract_client
+        .pause(&pauser, &PauseScope::Marketplace);
+    assert!(test_data
+        .contract_client
+        .is_paused(&PauseScope::Marketplace));
+    assert!(!test_data
+        .contract_client
+        .is_paused(&PauseScope::Registration));
+
+    // Other scopes keep working
+    test_data
+        .contract_client
+        .set_record(&domain, &tld, &owner, &owner, &test_data.min_duration);
 
     assert_eq!(
-        updated_core.allowed_tlds,
-        Vec::from_array(&e, [Bytes::from_slice(&e, "eth".as_bytes())])
+        test_data
+            .contract_client
+            .try_set_offer(&buyer, &node, &100_0000000)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::ContractPaused.into()
     );
+
+    // Only the admin can unpause the contract
+    test_data.contract_client.unpause(&PauseScope::Marketplace);
+    assert_eq!(e.auths().first().unwrap().0, test_data.adm);
+
+    test_data
+        .contract_client
+        .set_offer(&buyer, &node, &100_0000000);
+
+    test_data.contract_client.pause(&pauser, &PauseScope::All);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_transfer(&owner, &buyer, &node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::ContractPaused.into()
+    );
+
+    // Reading records and withdrawing the funds of the offers is always possible
+    assert!(test_data
+        .contract_client
+        .record(&RecordKeys::Record(node.clone()))
+        .is_some());
+    test_data
+        .contract_client
+        .burn_offer(&OffersDataKeys::BuyOffer(node.clone()));
+    assert_eq!(test_data.col_asset_client.balance(&buyer), 1000_0000000);
+}
+
+#[test]
+pub fn test_admin_handover() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let new_adm: Address = Address::generate(&e);
+
+    assert_eq!(test_data.contract_client.admin(), test_data.adm);
+    assert_eq!(test_data.contract_client.pending_admin(), None);
+
+    // Only the current admin can propose a new admin
+    assert!(test_data
+        .contract_client
+        .mock_auths(&[MockAuth {
+            address: &new_adm,
+            invoke: &MockAuthInvoke {
+                contract: &test_data.contract_client.address,
+                fn_name: "propose_admin",
+                args: (new_adm.clone(),).into_val(&e),
+                sub_invokes: &[],
+            },
+        }])
+        .try_propose_admin(&new_adm)
+        .is_err());
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .mock_all_auths()
+            .try_accept_admin()
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NoPendingAdmin.into()
+    );
+
+    test_data
+        .contract_client
+        .mock_auths(&[MockAuth {
+            address: &test_data.adm,
+            invoke: &MockAuthInvoke {
+                contract: &test_data.contract_client.address,
+                fn_name: "propose_admin",
+                args: (new_adm.clone(),).into_val(&e),
+                sub_invokes: &[],
+            },
+        }])
+        .propose_admin(&new_adm);
+
+    // The admin doesn't change until the new admin accepts it
+    assert_eq!(test_data.contract_client.admin(), test_data.adm);
+    assert_eq!(
+        test_data.contract_client.pending_admin(),
+        Some(new_adm.clone())
+    );
+
+    assert!(test_data
+        .contract_client
+        .mock_auths(&[MockAuth {
+            address: &test_data.adm,
+            invoke: &MockAuthInvoke {
+                contract: &test_data.contract_client.address,
+                fn_name: "accept_admin",
+                args: ().into_val(&e),
+                sub_invokes: &[],
+            },
+        }])
+        .try_accept_admin()
+        .is_err());
+
+    test_data
+        .contract_client
+        .mock_auths(&[MockAuth {
+            address: &new_adm,
+            invoke: &MockAuthInvoke {
+                contract: &test_data.contract_client.address,
+                fn_name: "accept_admin",
+                args: ().into_val(&e),
+                sub_invokes: &[],
+            },
+        }])
+        .accept_admin();
+
+    assert_eq!(test_data.contract_client.admin(), new_adm);
+    assert_eq!(test_data.contract_client.pending_admin(), None);
 }
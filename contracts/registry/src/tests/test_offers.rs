@@ -1,13 +1,18 @@
 #![cfg(test)]
 
 use crate::errors::ContractErrors;
+use crate::events::EventTopics;
+use crate::storage::core::CollateralConfig;
 use crate::storage::offers::{Offer, OffersDataKeys, OffersFunc};
 use crate::storage::record::{Domain, Record, RecordKeys};
-use crate::tests::test_utils::{create_test_data, init_contract, TestData};
+use crate::tests::test_utils::{create_oracle, create_test_data, init_contract, TestData};
+use crate::utils::quotes::OfferQuote;
 use crate::utils::records::generate_node;
 use num_integer::div_ceil;
-use soroban_sdk::testutils::{Address as _, BytesN as __, Ledger, MockAuth, MockAuthInvoke};
-use soroban_sdk::{Address, Bytes, BytesN, Env, IntoVal};
+use soroban_sdk::testutils::{
+    Address as _, BytesN as __, Events, Ledger, MockAuth, MockAuthInvoke,
+};
+use soroban_sdk::{vec, Address, Bytes, BytesN, Env, IntoVal, Vec};
 use test_utils::create_env;
 
 struct Users {
@@ -511,6 +516,29 @@ pub fn test_take_buy_offer() {
         fee,
         test_data.col_asset_client.balance(&test_data.fee_taker) as u128
     );
+    // The event keeps its original layout, the node is only part of the data
+    let mut accepted_events = Vec::new(&e);
+    for (contract_id, topics, data) in e.events().all().iter() {
+        if contract_id == test_data.contract_client.address
+            && topics == (EventTopics::OfferAccepted,).into_val(&e)
+        {
+            accepted_events.push_back(data);
+        }
+    }
+    assert_eq!(
+        accepted_events,
+        vec![
+            &e,
+            (
+                users.initial_buyer.clone(),
+                users.initial_user.clone(),
+                target_domain.clone(),
+                amount,
+                e.ledger().timestamp(),
+            )
+                .into_val(&e)
+        ]
+    );
 }
 
 #[test]
@@ -595,3 +623,190 @@ pub fn test_burn_offers() {
 pub fn test_take_offer_errors() {
     // todo!()
 }
+
+#[test]
+pub fn test_take_offer_below_collateral() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let (users, target_domain) = test_offers_start_data(&e, &test_data);
+    test_data
+        .col_asset_stellar
+        .mint(&users.initial_user, &100_0000000);
+
+    test_data
+        .contract_client
+        .set_offer(&users.initial_buyer, &target_domain, &68_4956841);
+
+    // The price of XLM goes down, so the renewal raises the collateral above the amount of the buy offer
+    let oracle: Address = create_oracle(&e, 0_1459946);
+    test_data
+        .contract_client
+        .set_oracle(&test_data.adm, &oracle);
+    test_data.contract_client.renew_record(
+        &users.initial_user,
+        &RecordKeys::Record(target_domain.clone()),
+        &test_data.min_duration,
+    );
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_take_offer(&users.initial_user, &target_domain)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidOfferAmount.into()
+    );
+}
+
+#[test]
+pub fn test_take_offer_undercollateralized() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let (users, target_domain) = test_offers_start_data(&e, &test_data);
+    test_data
+        .col_asset_stellar
+        .mint(&users.second_buyer, &200_0000000);
+
+    let amount: u128 = 200_0000000;
+    test_data
+        .contract_client
+        .set_offer(&users.initial_user, &target_domain, &amount);
+    test_data.contract_client.set_collateral_config(
+        &test_data.adm,
+        &CollateralConfig {
+            min_ratio: 50_0000000,
+        },
+    );
+
+    // The price of XLM goes down and the domain is below 50% of the collateral it needs, the buyer tops it up with the
+    // amount of the offer so the seller doesn't need to rebalance it first
+    let oracle: Address = create_oracle(&e, 0_1459946);
+    test_data
+        .contract_client
+        .set_oracle(&test_data.adm, &oracle);
+
+    let top_up: u128 = 136_9913681 - 68_4956840;
+    let fee: u128 = div_ceil((amount - 136_9913681) * test_data.offer_fee, 100_0000000);
+    let quote: OfferQuote = test_data
+        .contract_client
+        .quote_offer(&target_domain)
+        .get(0)
+        .unwrap();
+    assert_eq!(quote.collateral, 136_9913681);
+    assert_eq!(quote.fee, fee);
+    assert_eq!(quote.seller_amount, amount - top_up - fee);
+
+    let contract_balance_before_sale: i128 = test_data
+        .col_asset_client
+        .balance(&test_data.contract_client.address);
+    test_data
+        .contract_client
+        .take_offer(&users.second_buyer, &target_domain);
+
+    assert_eq!(
+        test_data
+            .col_asset_client
+            .balance(&test_data.contract_client.address),
+        contract_balance_before_sale + top_up as i128
+    );
+    assert_eq!(
+        test_data.col_asset_client.balance(&users.initial_user) as u128,
+        68_4956840 + amount - top_up - fee
+    );
+    assert_eq!(
+        test_data.col_asset_client.balance(&test_data.fee_taker) as u128,
+        fee
+    );
+
+    let domain: Domain = match test_data
+        .contract_client
+        .record(&RecordKeys::Record(target_domain.clone()))
+        .unwrap()
+    {
+        Record::Domain(value) => value,
+        Record::SubDomain(_) => panic!(),
+    };
+    assert_eq!(domain.owner, users.second_buyer);
+    assert_eq!(domain.collateral, 136_9913681);
+
+    e.ledger().set_timestamp(e.ledger().timestamp() + 1);
+    test_data
+        .contract_client
+        .transfer(&users.second_buyer, &users.initial_buyer, &target_domain);
+}
+
+#[test]
+pub fn test_offer_quotes() {
+    let e: Env = create_env();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let (users, target_domain) = test_offers_start_data(&e, &test_data);
+    assert_eq!(
+        test_data.contract_client.quote_offer(&target_domain).len(),
+        0
+    );
+
+    let sale_amount: u128 = 68_4956840u128 + 20_0000000;
+    let buy_amount: u128 = 68_4956840u128 + 10_0000000;
+    test_data.contract_client.mock_all_auths().set_offer(
+        &users.initial_user,
+        &target_domain,
+        &sale_amount,
+    );
+    test_data.contract_client.mock_all_auths().set_offer(
+        &users.initial_buyer,
+        &target_domain,
+        &buy_amount,
+    );
+
+    let sale_fee: u128 = div_ceil(20_0000000 * test_data.offer_fee, 100_0000000);
+    let buy_fee: u128 = div_ceil(10_0000000 * test_data.offer_fee, 100_0000000);
+    let quotes: Vec<OfferQuote> = test_data.contract_client.quote_offer(&target_domain);
+    assert_eq!(
+        quotes,
+        Vec::from_array(
+            &e,
+            [
+                OfferQuote {
+                    offer: OffersDataKeys::SaleOffer(target_domain.clone()),
+                    amount: sale_amount,
+                    collateral: 68_4956840,
+                    fee: sale_fee,
+                    seller_amount: sale_amount - sale_fee,
+                    outdated: false,
+                },
+                OfferQuote {
+                    offer: OffersDataKeys::BuyOffer(target_domain.clone()),
+                    amount: buy_amount,
+                    collateral: 68_4956840,
+                    fee: buy_fee,
+                    seller_amount: buy_amount - buy_fee,
+                    outdated: false,
+                },
+            ]
+        )
+    );
+
+    // The seller receives the amount of the quote and the buy offer gets outdated once the domain is sold
+    e.ledger().with_mut(|ledger| ledger.timestamp += 60);
+    let balance: i128 = test_data.col_asset_client.balance(&users.initial_user);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .take_offer(&users.second_buyer, &target_domain);
+    assert_eq!(
+        test_data.col_asset_client.balance(&users.initial_user) - balance,
+        (sale_amount - sale_fee) as i128
+    );
+
+    let quotes: Vec<OfferQuote> = test_data.contract_client.quote_offer(&target_domain);
+    assert_eq!(quotes.len(), 1);
+    assert!(quotes.get(0).unwrap().outdated);
+}
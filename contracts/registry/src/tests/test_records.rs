@@ -1,11 +1,24 @@
 #![cfg(test)]
 
 use crate::errors::ContractErrors;
-use crate::storage::record::{Domain, Record, RecordKeys, SubDomain};
-use crate::tests::test_utils::{create_test_data, init_contract, TestData};
-use crate::utils::records::generate_node;
-use soroban_sdk::testutils::{Address as _, MockAuth, MockAuthInvoke};
-use soroban_sdk::{Address, Bytes, BytesN, Env, IntoVal};
+use crate::events::EventTopics;
+use crate::storage::core::{
+    CollateralConfig, CommitmentsConfig, DurationConfig, LiquidationConfig, OracleConfig,
+    MAX_GRACE_PERIOD,
+};
+use crate::storage::offers::OffersDataKeys;
+use crate::storage::owners::OwnersEntity;
+use crate::storage::pricing::PricingTable;
+use crate::storage::record::{Domain, Record, RecordEntity, RecordKeys, SubDomain};
+use crate::storage::subdomains::SubdomainsEntity;
+use crate::storage::tlds::{NameRules, TldConfig, TldStatus};
+use crate::tests::test_utils::{
+    create_oracle, create_test_data, init_contract, set_oracle_price, TestData,
+};
+use crate::utils::quotes::{RebalanceQuote, RegistrationQuote, RenewalQuote};
+use crate::utils::records::{generate_node, MAX_BURNED_SUBDOMAINS};
+use soroban_sdk::testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke};
+use soroban_sdk::{vec, Address, Bytes, BytesN, Env, IntoVal, Vec};
 use test_utils::create_env;
 
 #[test]
@@ -141,7 +154,7 @@ fn test_invalid_domain() {
 
     assert_eq!(
         domain_with_numeric_value_error,
-        ContractErrors::InvalidDomain.into()
+        ContractErrors::InvalidCharacter.into()
     );
 
     let domain_with_uppercase_error = test_data
@@ -158,7 +171,7 @@ fn test_invalid_domain() {
 
     assert_eq!(
         domain_with_uppercase_error,
-        ContractErrors::InvalidDomain.into()
+        ContractErrors::InvalidCharacter.into()
     );
 
     let too_large_domain_error = test_data
@@ -176,7 +189,307 @@ fn test_invalid_domain() {
         .unwrap_err()
         .unwrap();
 
-    assert_eq!(too_large_domain_error, ContractErrors::InvalidDomain.into());
+    assert_eq!(too_large_domain_error, ContractErrors::NameTooLong.into());
+}
+
+#[test]
+fn test_name_rules() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    test_data.col_asset_stellar.mint(&owner, &10_000_0000000);
+
+    let config: TldConfig = test_data.contract_client.tld_config(&tld).unwrap();
+    test_data.contract_client.update_tld(
+        &test_data.adm,
+        &tld,
+        &TldConfig {
+            name_rules: NameRules {
+                min_length: 3,
+                max_length: 20,
+                allow_digits: true,
+                allow_hyphens: true,
+                allow_idn: false,
+            },
+            ..config
+        },
+    );
+
+    for (domain, error) in [
+        ("ab", ContractErrors::NameTooShort),
+        ("stellar-community-name", ContractErrors::NameTooLong),
+        ("my_shop", ContractErrors::InvalidCharacter),
+        ("-shop", ContractErrors::InvalidHyphen),
+        ("shop-", ContractErrors::InvalidHyphen),
+        ("my--shop", ContractErrors::InvalidHyphen),
+    ] {
+        assert_eq!(
+            test_data
+                .contract_client
+                .try_set_record(
+                    &Bytes::from_slice(&e, domain.as_bytes()),
+                    &tld,
+                    &owner,
+                    &owner,
+                    &test_data.min_duration,
+                )
+                .unwrap_err()
+                .unwrap(),
+            error.into()
+        );
+    }
+
+    for domain in ["web3", "my-shop", "stellar2024"] {
+        test_data.contract_client.set_record(
+            &Bytes::from_slice(&e, domain.as_bytes()),
+            &tld,
+            &owner,
+            &owner,
+            &test_data.min_duration,
+        );
+    }
+
+    // Blocked names can't be registered until they are unblocked
+    let blocked: Bytes = Bytes::from_slice(&e, "admin".as_bytes());
+    test_data
+        .contract_client
+        .set_blocked_name(&test_data.adm, &blocked, &tld, &true);
+    assert!(test_data.contract_client.is_blocked_name(&blocked, &tld));
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_record(&blocked, &tld, &owner, &owner, &test_data.min_duration)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::BlockedName.into()
+    );
+
+    test_data
+        .contract_client
+        .set_blocked_name(&test_data.adm, &blocked, &tld, &false);
+    test_data
+        .contract_client
+        .set_record(&blocked, &tld, &owner, &owner, &test_data.min_duration);
+}
+
+#[test]
+fn test_internationalized_names() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    test_data.col_asset_stellar.mint(&owner, &10_000_0000000);
+
+    // Internationalized names are rejected unless the TLD allows them
+    let cafe: Bytes = Bytes::from_slice(&e, "xn--caf-dma".as_bytes());
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_record(&cafe, &tld, &owner, &owner, &test_data.min_duration)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidCharacter.into()
+    );
+
+    let config: TldConfig = test_data.contract_client.tld_config(&tld).unwrap();
+    test_data.contract_client.update_tld(
+        &test_data.adm,
+        &tld,
+        &TldConfig {
+            name_rules: NameRules {
+                min_length: 1,
+                max_length: 5,
+                allow_digits: false,
+                allow_hyphens: false,
+                allow_idn: true,
+            },
+            ..config
+        },
+    );
+
+    for (domain, error) in [
+        // `xn--` followed by an ascii only label
+        ("xn--abc-", ContractErrors::InvalidPunycode),
+        // `café` with uppercase punycode digits, only the canonical form is accepted
+        ("xn--caf-DMA", ContractErrors::InvalidPunycode),
+        // `Café`
+        ("xn--Caf-dma", ContractErrors::InvalidCharacter),
+        // `café` written with a combining accent
+        ("xn--cafe-yvc", ContractErrors::InvalidCharacter),
+        // `аpple` with a Cyrillic `а`
+        ("xn--pple-43d", ContractErrors::MixedScripts),
+        // `аре` in Cyrillic looks like `ape`
+        ("xn--80ak6a", ContractErrors::ConfusableName),
+        // `όχι` in Greek looks like `óxi`
+        ("xn--uxa1ao", ContractErrors::ConfusableName),
+        // `пример` has six characters
+        ("xn--e1afmkfd", ContractErrors::NameTooLong),
+    ] {
+        assert_eq!(
+            test_data
+                .contract_client
+                .try_set_record(
+                    &Bytes::from_slice(&e, domain.as_bytes()),
+                    &tld,
+                    &owner,
+                    &owner,
+                    &test_data.min_duration,
+                )
+                .unwrap_err()
+                .unwrap(),
+            error.into()
+        );
+    }
+
+    // `日本語`
+    test_data.contract_client.set_record(
+        &Bytes::from_slice(&e, "xn--wgv71a119e".as_bytes()),
+        &tld,
+        &owner,
+        &owner,
+        &test_data.min_duration,
+    );
+
+    // `café` is priced as a name with four characters
+    let balance: i128 = test_data.col_asset_client.balance(&owner);
+    test_data
+        .contract_client
+        .set_record(&cafe, &tld, &owner, &owner, &test_data.min_duration);
+    let idn_price: i128 = balance - test_data.col_asset_client.balance(&owner);
+
+    let balance: i128 = test_data.col_asset_client.balance(&owner);
+    test_data.contract_client.set_record(
+        &Bytes::from_slice(&e, "cafe".as_bytes()),
+        &tld,
+        &owner,
+        &owner,
+        &test_data.min_duration,
+    );
+    assert_eq!(
+        balance - test_data.col_asset_client.balance(&owner),
+        idn_price
+    );
+}
+
+#[test]
+fn test_resolve() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let domain_address: Address = Address::generate(&e);
+    let b_address: Address = Address::generate(&e);
+    let a_address: Address = Address::generate(&e);
+    let domain: Bytes = Bytes::from_slice(&e, "example".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    test_data.col_asset_stellar.mint(&owner, &10_000_0000000);
+
+    test_data.contract_client.set_record(
+        &domain,
+        &tld,
+        &owner,
+        &domain_address,
+        &test_data.min_duration,
+    );
+
+    let domain_node: BytesN<32> = generate_node(&e, &domain, &tld);
+    let b: Bytes = Bytes::from_slice(&e, "b".as_bytes());
+    let b_node: BytesN<32> = generate_node(&e, &b, &Bytes::from(domain_node.clone()));
+    test_data
+        .contract_client
+        .set_sub(&b, &RecordKeys::Record(domain_node.clone()), &b_address);
+    test_data.contract_client.set_sub(
+        &Bytes::from_slice(&e, "a".as_bytes()),
+        &RecordKeys::SubRecord(b_node.clone()),
+        &a_address,
+    );
+
+    for (name, address) in [
+        ("example.xlm", &domain_address),
+        ("b.example.xlm", &b_address),
+        ("a.b.example.xlm", &a_address),
+    ] {
+        assert_eq!(
+            test_data
+                .contract_client
+                .resolve(&Bytes::from_slice(&e, name.as_bytes())),
+            Some(address.clone())
+        );
+    }
+
+    let a_node: BytesN<32> = generate_node(
+        &e,
+        &Bytes::from_slice(&e, "a".as_bytes()),
+        &Bytes::from(b_node.clone()),
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .resolve_record(&Bytes::from_slice(&e, "a.b.example.xlm".as_bytes())),
+        test_data
+            .contract_client
+            .record(&RecordKeys::SubRecord(a_node))
+            .unwrap()
+    );
+
+    for (name, error) in [
+        ("example", ContractErrors::InvalidDomain),
+        ("Example.xlm", ContractErrors::InvalidCharacter),
+        ("a..example.xlm", ContractErrors::NameTooShort),
+        ("c.example.xlm", ContractErrors::RecordDoesntExist),
+        ("example.eth", ContractErrors::RecordDoesntExist),
+        ("a.b.c.d.e.f.example.xlm", ContractErrors::MaxDepthExceeded),
+    ] {
+        let name: Bytes = Bytes::from_slice(&e, name.as_bytes());
+        assert_eq!(test_data.contract_client.resolve(&name), None);
+        assert_eq!(
+            test_data
+                .contract_client
+                .try_resolve_record(&name)
+                .unwrap_err()
+                .unwrap(),
+            error
+        );
+    }
+
+    // Subdomains are outdated once the domain is transferred
+    e.ledger().with_mut(|ledger| ledger.timestamp += 60);
+    test_data
+        .contract_client
+        .transfer(&owner, &Address::generate(&e), &domain_node);
+    let name: Bytes = Bytes::from_slice(&e, "b.example.xlm".as_bytes());
+    assert_eq!(test_data.contract_client.resolve(&name), None);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_resolve_record(&name)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::OutdatedSub
+    );
+
+    // Expired domains don't resolve
+    e.ledger()
+        .with_mut(|ledger| ledger.timestamp += test_data.min_duration);
+    let name: Bytes = Bytes::from_slice(&e, "example.xlm".as_bytes());
+    assert_eq!(test_data.contract_client.resolve(&name), None);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_resolve_record(&name)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::ExpiredDomain
+    );
 }
 
 #[test]
@@ -297,64 +610,2258 @@ fn test_subdomains() {
 }
 
 #[test]
-fn test_updating_address() {
+fn test_nested_subdomains() {
     let e: Env = create_env();
+    e.mock_all_auths();
     let test_data: TestData = create_test_data(&e);
     init_contract(&e, &test_data);
 
     let owner: Address = Address::generate(&e);
-    let address: Address = Address::generate(&e);
-    let second_address: Address = Address::generate(&e);
-
-    let new_domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    let domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
     let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    let duration: u64 = test_data.min_duration;
 
-    test_data.col_asset_stellar.mock_all_auths().mint(
-        &owner,
-        &(test_data.min_duration as i128 * test_data.node_rate as i128),
-    );
+    test_data.col_asset_stellar.mint(&owner, &i128::MAX);
+    test_data
+        .contract_client
+        .set_record(&domain, &tld, &owner, &owner, &duration);
 
-    test_data.contract_client.mock_all_auths().set_record(
-        &new_domain,
-        &tld,
-        &owner,
-        &address,
-        &test_data.min_duration,
-    );
+    // `a.b.stellar.xlm`
+    let domain_node: BytesN<32> = generate_node(&e, &domain, &tld);
+    let b_sub: Bytes = Bytes::from_slice(&e, "b".as_bytes());
+    let b_node: BytesN<32> = generate_node(&e, &b_sub, &Bytes::from(domain_node.clone()));
+    let a_sub: Bytes = Bytes::from_slice(&e, "a".as_bytes());
+    let a_node: BytesN<32> = generate_node(&e, &a_sub, &Bytes::from(b_node.clone()));
+    let a_address: Address = Address::generate(&e);
 
-    let node: BytesN<32> = test_data.contract_client.parse_domain(&new_domain, &tld);
+    test_data
+        .contract_client
+        .set_sub(&b_sub, &RecordKeys::Record(domain_node.clone()), &owner);
+    test_data
+        .contract_client
+        .set_sub(&a_sub, &RecordKeys::SubRecord(b_node.clone()), &a_address);
 
-    let first_record: Domain = match test_data
+    let a_record: SubDomain = match test_data
         .contract_client
-        .record(&RecordKeys::Record(node.clone()))
+        .record(&RecordKeys::SubRecord(a_node.clone()))
         .unwrap()
     {
-        Record::Domain(domain) => domain,
-        Record::SubDomain(_) => panic!(),
+        Record::Domain(_) => panic!(),
+        Record::SubDomain(sub) => sub,
     };
 
+    assert_eq!(a_record.address, a_address);
+    assert_eq!(a_record.parent, b_node);
+
+    // The parent must exist
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_sub(
+                &a_sub,
+                &RecordKeys::SubRecord(generate_node(&e, &a_sub, &Bytes::from(a_node.clone()))),
+                &a_address,
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidParent.into()
+    );
+
+    // `a.b.stellar.xlm` has a depth of 2, so it can't have children if the max depth is 2
+    test_data.contract_client.set_subdomains_config(&2, &true);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_sub(&a_sub, &RecordKeys::SubRecord(a_node.clone()), &a_address)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::MaxDepthExceeded.into()
+    );
+
+    // Transferring the root domain invalidates all the nested subdomains
+    e.ledger().set_timestamp(e.ledger().timestamp() + 1);
     test_data
         .contract_client
-        .mock_auths(&[MockAuth {
-            address: &owner,
-            invoke: &MockAuthInvoke {
-                contract: &test_data.contract_client.address,
-                fn_name: "update_address",
-                args: (RecordKeys::Record(node.clone()), second_address.clone()).into_val(&e),
-                sub_invokes: &[],
-            },
-        }])
-        .update_address(&RecordKeys::Record(node.clone()), &second_address);
+        .transfer(&owner, &Address::generate(&e), &domain_node);
 
-    let second_record: Domain = match test_data
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_record(&RecordKeys::SubRecord(a_node.clone()))
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::OutdatedSub.into()
+    );
+}
+
+#[test]
+fn test_subdomains_index() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let new_owner: Address = Address::generate(&e);
+    let domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+
+    test_data.col_asset_stellar.mint(&owner, &i128::MAX);
+    test_data
         .contract_client
-        .record(&RecordKeys::Record(node.clone()))
-        .unwrap()
-    {
-        Record::Domain(domain) => domain,
-        Record::SubDomain(_) => panic!(),
-    };
+        .set_record(&domain, &tld, &owner, &owner, &test_data.min_duration);
 
-    assert_eq!(address, first_record.address);
-    assert_eq!(second_address, second_record.address);
+    // `a.b.stellar.xlm` and `c.stellar.xlm`
+    let domain_node: BytesN<32> = generate_node(&e, &domain, &tld);
+    let b_sub: Bytes = Bytes::from_slice(&e, "b".as_bytes());
+    let b_node: BytesN<32> = generate_node(&e, &b_sub, &Bytes::from(domain_node.clone()));
+    let c_sub: Bytes = Bytes::from_slice(&e, "c".as_bytes());
+    let c_node: BytesN<32> = generate_node(&e, &c_sub, &Bytes::from(domain_node.clone()));
+    let a_sub: Bytes = Bytes::from_slice(&e, "a".as_bytes());
+    let a_node: BytesN<32> = generate_node(&e, &a_sub, &Bytes::from(b_node.clone()));
+
+    test_data
+        .contract_client
+        .set_sub(&b_sub, &RecordKeys::Record(domain_node.clone()), &owner);
+    test_data
+        .contract_client
+        .set_sub(&c_sub, &RecordKeys::Record(domain_node.clone()), &owner);
+    test_data
+        .contract_client
+        .set_sub(&a_sub, &RecordKeys::SubRecord(b_node.clone()), &owner);
+
+    // Updating a subdomain doesn't add it again
+    test_data
+        .contract_client
+        .set_sub(&b_sub, &RecordKeys::Record(domain_node.clone()), &owner);
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .subdomains_of(&domain_node, &0, &10),
+        vec![&e, b_node.clone(), c_node.clone()]
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .subdomains_of(&domain_node, &1, &1),
+        vec![&e, c_node.clone()]
+    );
+    assert_eq!(
+        test_data.contract_client.subdomains_of(&b_node, &0, &10),
+        vec![&e, a_node.clone()]
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .subdomains_of(&a_node, &0, &10)
+            .len(),
+        0
+    );
+
+    test_data
+        .contract_client
+        .burn_record(&RecordKeys::SubRecord(c_node.clone()));
+    assert_eq!(
+        test_data
+            .contract_client
+            .subdomains_of(&domain_node, &0, &10),
+        vec![&e, b_node.clone()]
+    );
+
+    // Once the root domain is transferred, the old subdomains are outdated and they are not listed
+    e.ledger().set_timestamp(e.ledger().timestamp() + 1);
+    test_data
+        .contract_client
+        .transfer(&owner, &new_owner, &domain_node);
+    assert_eq!(
+        test_data
+            .contract_client
+            .subdomains_of(&domain_node, &0, &10)
+            .len(),
+        0
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .subdomains_of(&b_node, &0, &10)
+            .len(),
+        0
+    );
+
+    test_data
+        .contract_client
+        .set_sub(&b_sub, &RecordKeys::Record(domain_node.clone()), &new_owner);
+    assert_eq!(
+        test_data
+            .contract_client
+            .subdomains_of(&domain_node, &0, &10),
+        vec![&e, b_node.clone()]
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .subdomains_of(&b_node, &0, &10)
+            .len(),
+        0
+    );
+}
+
+#[test]
+fn test_indexes_keys() {
+    let e: Env = create_env();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let parent: BytesN<32> = BytesN::from_array(&e, &[1; 32]);
+    let first_node: BytesN<32> = BytesN::from_array(&e, &[2; 32]);
+    let second_node: BytesN<32> = BytesN::from_array(&e, &[3; 32]);
+
+    // The same node in the list of an owner and in the list of a parent keeps a different position in each list
+    e.as_contract(&test_data.contract_client.address, || {
+        e.add_owned_domain(&owner, &first_node);
+        e.add_owned_domain(&owner, &second_node);
+        e.add_subdomain(&parent, &second_node);
+
+        assert_eq!(e.domains_balance(&owner), 2);
+        assert_eq!(e.subdomains_count(&parent), 1);
+        assert_eq!(e.subdomain(&parent, 0), Some(second_node.clone()));
+
+        e.remove_subdomain(&parent, &second_node);
+        assert_eq!(e.subdomains_count(&parent), 0);
+        assert_eq!(e.owned_domain(&owner, 1), Some(second_node.clone()));
+
+        e.remove_owned_domain(&owner, &first_node);
+        assert_eq!(e.domains_balance(&owner), 1);
+        assert_eq!(e.owned_domain(&owner, 0), Some(second_node.clone()));
+    });
+}
+
+#[test]
+fn test_burning_records() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let buyer: Address = Address::generate(&e);
+    let domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+
+    test_data.col_asset_stellar.mint(&owner, &1_000_0000000);
+    test_data.col_asset_stellar.mint(&buyer, &1_000_0000000);
+    test_data
+        .contract_client
+        .set_record(&domain, &tld, &owner, &owner, &test_data.min_duration);
+
+    // `a.b.stellar.xlm` and `c.stellar.xlm`
+    let domain_node: BytesN<32> = generate_node(&e, &domain, &tld);
+    let b_sub: Bytes = Bytes::from_slice(&e, "b".as_bytes());
+    let b_node: BytesN<32> = generate_node(&e, &b_sub, &Bytes::from(domain_node.clone()));
+    let c_sub: Bytes = Bytes::from_slice(&e, "c".as_bytes());
+    let c_node: BytesN<32> = generate_node(&e, &c_sub, &Bytes::from(domain_node.clone()));
+    let a_sub: Bytes = Bytes::from_slice(&e, "a".as_bytes());
+    let a_node: BytesN<32> = generate_node(&e, &a_sub, &Bytes::from(b_node.clone()));
+
+    test_data
+        .contract_client
+        .set_sub(&b_sub, &RecordKeys::Record(domain_node.clone()), &owner);
+    test_data
+        .contract_client
+        .set_sub(&c_sub, &RecordKeys::Record(domain_node.clone()), &owner);
+    test_data
+        .contract_client
+        .set_sub(&a_sub, &RecordKeys::SubRecord(b_node.clone()), &owner);
+
+    // Burning a subdomain removes its record
+    test_data
+        .contract_client
+        .burn_record(&RecordKeys::SubRecord(c_node.clone()));
+    assert_eq!(
+        test_data
+            .contract_client
+            .record(&RecordKeys::SubRecord(c_node.clone())),
+        None
+    );
+
+    test_data
+        .contract_client
+        .set_offer(&owner, &domain_node, &500_0000000);
+    test_data
+        .contract_client
+        .set_offer(&buyer, &domain_node, &300_0000000);
+    assert_eq!(test_data.col_asset_client.balance(&buyer), 700_0000000);
+
+    // Burning the domain burns all its subdomains and cancels its offers
+    test_data
+        .contract_client
+        .burn_record(&RecordKeys::Record(domain_node.clone()));
+    for key in [
+        RecordKeys::Record(domain_node.clone()),
+        RecordKeys::SubRecord(b_node.clone()),
+        RecordKeys::SubRecord(a_node.clone()),
+    ] {
+        assert_eq!(test_data.contract_client.record(&key), None);
+    }
+    assert_eq!(test_data.col_asset_client.balance(&buyer), 1_000_0000000);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_burn_offer(&OffersDataKeys::BuyOffer(domain_node.clone()))
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::OfferDoesntExist.into()
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_burn_offer(&OffersDataKeys::SaleOffer(domain_node.clone()))
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::OfferDoesntExist.into()
+    );
+    assert_eq!(
+        test_data
+            .col_asset_client
+            .balance(&test_data.contract_client.address),
+        0
+    );
+}
+
+#[test]
+fn test_burning_many_subdomains() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    let domain_node: BytesN<32> = generate_node(&e, &domain, &tld);
+
+    test_data.col_asset_stellar.mint(&owner, &68_4956840);
+    test_data
+        .contract_client
+        .set_record(&domain, &tld, &owner, &owner, &test_data.min_duration);
+
+    // 5 subdomains with 5 children each, more than what a single call can burn
+    let mut nodes: Vec<BytesN<32>> = Vec::new(&e);
+    for i in 0..5u8 {
+        let sub: Bytes = Bytes::from_slice(&e, &[b'a' + i]);
+        let sub_node: BytesN<32> = generate_node(&e, &sub, &Bytes::from(domain_node.clone()));
+        test_data
+            .contract_client
+            .set_sub(&sub, &RecordKeys::Record(domain_node.clone()), &owner);
+        nodes.push_back(sub_node.clone());
+
+        for j in 0..5u8 {
+            let child: Bytes = Bytes::from_slice(&e, &[b'a' + j]);
+            test_data.contract_client.set_sub(
+                &child,
+                &RecordKeys::SubRecord(sub_node.clone()),
+                &owner,
+            );
+            nodes.push_back(generate_node(&e, &child, &Bytes::from(sub_node.clone())));
+        }
+    }
+
+    // The domain is burned right away and its subdomains are burned from the end of the list until the limit is reached
+    test_data
+        .contract_client
+        .burn_record(&RecordKeys::Record(domain_node.clone()));
+
+    let existing_subdomains = |e: &Env| -> u32 {
+        e.as_contract(&test_data.contract_client.address, || {
+            nodes
+                .iter()
+                .filter(|node| e.record(&RecordKeys::SubRecord(node.clone())).is_some())
+                .count() as u32
+        })
+    };
+    assert_eq!(existing_subdomains(&e), 30 - MAX_BURNED_SUBDOMAINS);
+
+    // The rest can be burned by anyone once the parent is gone
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_burn_orphan_subdomains(&nodes.get(0).unwrap())
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidParent.into()
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .burn_orphan_subdomains(&domain_node),
+        0
+    );
+    assert_eq!(existing_subdomains(&e), 0);
+    assert_eq!(
+        test_data
+            .contract_client
+            .subdomains_of(&domain_node, &0, &10),
+        Vec::new(&e)
+    );
+}
+
+#[test]
+fn test_registering_domain_with_leftover_subdomains() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let new_owner: Address = Address::generate(&e);
+    let domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    let domain_node: BytesN<32> = generate_node(&e, &domain, &tld);
+
+    test_data.col_asset_stellar.mint(&owner, &68_4956840);
+    test_data.col_asset_stellar.mint(&new_owner, &i128::MAX);
+    test_data
+        .contract_client
+        .set_record(&domain, &tld, &owner, &owner, &test_data.min_duration);
+
+    // More subdomains than what a registration can burn
+    let mut nodes: Vec<BytesN<32>> = Vec::new(&e);
+    for i in 0..(MAX_BURNED_SUBDOMAINS + 5) {
+        let sub: Bytes = Bytes::from_slice(&e, &[b'a' + (i / 10) as u8, b'a' + (i % 10) as u8]);
+        test_data
+            .contract_client
+            .set_sub(&sub, &RecordKeys::Record(domain_node.clone()), &owner);
+        nodes.push_back(generate_node(&e, &sub, &Bytes::from(domain_node.clone())));
+    }
+
+    let existing_subdomains = |e: &Env| -> u32 {
+        e.as_contract(&test_data.contract_client.address, || {
+            nodes
+                .iter()
+                .filter(|node| e.record(&RecordKeys::SubRecord(node.clone())).is_some())
+                .count() as u32
+        })
+    };
+
+    // The subdomains of an active domain can't be burned
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_burn_orphan_subdomains(&domain_node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidParent.into()
+    );
+
+    e.ledger()
+        .set_timestamp(1742825701 + test_data.min_duration + (3600 * 24 * 30) + 1);
+    set_oracle_price(&e, 2919892);
+
+    // The leftovers need to be burned before the domain can be registered again
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_record(
+                &domain,
+                &tld,
+                &new_owner,
+                &new_owner,
+                &test_data.min_duration
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::PendingSubdomains.into()
+    );
+    assert_eq!(existing_subdomains(&e), MAX_BURNED_SUBDOMAINS + 5);
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .burn_orphan_subdomains(&domain_node),
+        5
+    );
+    assert_eq!(existing_subdomains(&e), 5);
+
+    // The registration burns the rest, so the new owner starts with an empty list
+    test_data.contract_client.set_record(
+        &domain,
+        &tld,
+        &new_owner,
+        &new_owner,
+        &test_data.min_duration,
+    );
+    assert_eq!(existing_subdomains(&e), 0);
+    assert_eq!(
+        test_data
+            .contract_client
+            .subdomains_of(&domain_node, &0, &10),
+        Vec::new(&e)
+    );
+}
+
+#[test]
+fn test_subdomains_ownership() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let team_owner: Address = Address::generate(&e);
+    let domain: Bytes = Bytes::from_slice(&e, "company".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+
+    test_data.col_asset_stellar.mint(&owner, &i128::MAX);
+    test_data
+        .contract_client
+        .set_record(&domain, &tld, &owner, &owner, &test_data.min_duration);
+
+    // `team.company.xlm` and `dev.team.company.xlm`
+    let domain_node: BytesN<32> = generate_node(&e, &domain, &tld);
+    let team_sub: Bytes = Bytes::from_slice(&e, "team".as_bytes());
+    let team_node: BytesN<32> = generate_node(&e, &team_sub, &Bytes::from(domain_node.clone()));
+    let dev_sub: Bytes = Bytes::from_slice(&e, "dev".as_bytes());
+    let dev_node: BytesN<32> = generate_node(&e, &dev_sub, &Bytes::from(team_node.clone()));
+
+    test_data
+        .contract_client
+        .set_sub(&team_sub, &RecordKeys::Record(domain_node.clone()), &owner);
+    assert_eq!(test_data.contract_client.sub_owner(&team_node), None);
+
+    // Without an owner, the subdomain is managed by the owner of the parent
+    test_data
+        .contract_client
+        .transfer_sub(&team_node, &team_owner);
+    assert_eq!(e.auths().first().unwrap().0, owner);
+    assert_eq!(
+        test_data.contract_client.sub_owner(&team_node),
+        Some(team_owner.clone())
+    );
+
+    // Now the new owner is the one who signs the updates and creates the children
+    let team_address: Address = Address::generate(&e);
+    test_data
+        .contract_client
+        .update_sub_address(&team_node, &team_address);
+    assert_eq!(e.auths().first().unwrap().0, team_owner);
+
+    test_data.contract_client.set_sub(
+        &dev_sub,
+        &RecordKeys::SubRecord(team_node.clone()),
+        &team_address,
+    );
+    assert_eq!(e.auths().first().unwrap().0, team_owner);
+
+    let team_record: SubDomain = match test_data
+        .contract_client
+        .record(&RecordKeys::SubRecord(team_node.clone()))
+        .unwrap()
+    {
+        Record::Domain(_) => panic!(),
+        Record::SubDomain(sub) => sub,
+    };
+    assert_eq!(team_record.address, team_address);
+
+    // The owner of the parent can't replace an owned subdomain, it needs to revoke it first
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_sub(&team_sub, &RecordKeys::Record(domain_node.clone()), &owner)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::SubdomainOwned.into()
+    );
+
+    test_data.contract_client.revoke_sub(&team_node);
+    assert_eq!(e.auths().first().unwrap().0, owner);
+    assert_eq!(test_data.contract_client.sub_owner(&team_node), None);
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_revoke_sub(&team_node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::SubdomainNotOwned.into()
+    );
+
+    // If revoking is disabled, the parent can't take back the subdomain
+    test_data
+        .contract_client
+        .transfer_sub(&team_node, &team_owner);
+    test_data.contract_client.set_subdomains_config(&5, &false);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_revoke_sub(&team_node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::RevokeDisabled.into()
+    );
+
+    // Once the root domain is transferred, the old owner of the subdomain is removed when the subdomain is set again
+    e.ledger().set_timestamp(e.ledger().timestamp() + 1);
+    test_data
+        .contract_client
+        .transfer(&owner, &owner, &domain_node);
+    test_data
+        .contract_client
+        .set_sub(&team_sub, &RecordKeys::Record(domain_node.clone()), &owner);
+    assert_eq!(test_data.contract_client.sub_owner(&team_node), None);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_record(&RecordKeys::SubRecord(dev_node.clone()))
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::OutdatedSub.into()
+    );
+}
+
+#[test]
+fn test_pricing_tables() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    let premium_domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    let domain: Bytes = Bytes::from_slice(&e, "soroban".as_bytes());
+
+    test_data.col_asset_stellar.mint(&owner, &i128::MAX);
+
+    // Before setting any table, we use the old prices
+    assert_eq!(
+        test_data.contract_client.pricing_table(&None),
+        PricingTable {
+            length_prices: vec![
+                &e,
+                187_5700000,
+                107_1800000,
+                61_2500000,
+                35_0000000,
+                20_0000000
+            ],
+        }
+    );
+
+    let xlm_table: PricingTable = PricingTable {
+        length_prices: vec![&e, 100_0000000, 10_0000000],
+    };
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_pricing_table(
+                &test_data.adm,
+                &None,
+                &Some(PricingTable {
+                    length_prices: vec![&e],
+                })
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidConfig.into()
+    );
+
+    test_data.contract_client.set_pricing_table(
+        &test_data.adm,
+        &Some(tld.clone()),
+        &Some(xlm_table.clone()),
+    );
+    test_data.contract_client.set_premium_price(
+        &test_data.adm,
+        &premium_domain,
+        &tld,
+        &Some(100_0000000),
+    );
+
+    assert_eq!(
+        test_data.contract_client.pricing_table(&Some(tld.clone())),
+        xlm_table
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .premium_price(&premium_domain, &tld),
+        Some(100_0000000)
+    );
+
+    // 10 USD
+    test_data
+        .contract_client
+        .set_record(&domain, &tld, &owner, &owner, &test_data.min_duration);
+    assert_eq!(
+        test_data
+            .col_asset_client
+            .balance(&test_data.contract_client.address),
+        34_2478420
+    );
+
+    // 100 USD
+    test_data.contract_client.set_record(
+        &premium_domain,
+        &tld,
+        &owner,
+        &owner,
+        &test_data.min_duration,
+    );
+    assert_eq!(
+        test_data
+            .col_asset_client
+            .balance(&test_data.contract_client.address),
+        34_2478420 + 342_4784204
+    );
+}
+
+#[test]
+fn test_oracle_config() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    let base_config: OracleConfig = test_data.contract_client.oracle_config();
+
+    test_data.col_asset_stellar.mint(&owner, &i128::MAX);
+
+    // Prices older than the max age are rejected
+    test_data.contract_client.set_oracle_config(
+        &test_data.adm,
+        &OracleConfig {
+            max_age: 300,
+            ..base_config.clone()
+        },
+    );
+    e.ledger().set_timestamp(e.ledger().timestamp() + 400);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_record(
+                &Bytes::from_slice(&e, "stale".as_bytes()),
+                &tld,
+                &owner,
+                &owner,
+                &test_data.min_duration,
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::StalePrice.into()
+    );
+
+    // If the main oracle price is stale, we use the fallback oracle
+    let fallback: Address = create_oracle(&e, 3919892);
+    test_data.contract_client.set_oracle_config(
+        &test_data.adm,
+        &OracleConfig {
+            max_age: 300,
+            fallback: Some(fallback.clone()),
+            ..base_config.clone()
+        },
+    );
+    test_data.contract_client.set_record(
+        &Bytes::from_slice(&e, "fallback".as_bytes()),
+        &tld,
+        &owner,
+        &owner,
+        &test_data.min_duration,
+    );
+    assert_eq!(
+        test_data
+            .col_asset_client
+            .balance(&test_data.contract_client.address),
+        51_0218138
+    );
+
+    // The average of the last two prices: (0.2919892 + 0.3919892) / 2
+    set_oracle_price(&e, 3919892);
+    test_data.contract_client.set_oracle_config(
+        &test_data.adm,
+        &OracleConfig {
+            twap_records: 2,
+            ..base_config.clone()
+        },
+    );
+    let balance: i128 = test_data
+        .col_asset_client
+        .balance(&test_data.contract_client.address);
+    test_data.contract_client.set_record(
+        &Bytes::from_slice(&e, "twapped".as_bytes()),
+        &tld,
+        &owner,
+        &owner,
+        &test_data.min_duration,
+    );
+    assert_eq!(
+        test_data
+            .col_asset_client
+            .balance(&test_data.contract_client.address)
+            - balance,
+        58_4813789
+    );
+
+    // Prices out of the bounds are rejected
+    test_data.contract_client.set_oracle_config(
+        &test_data.adm,
+        &OracleConfig {
+            max_price: 0_3000000,
+            ..base_config.clone()
+        },
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_record(
+                &Bytes::from_slice(&e, "bounded".as_bytes()),
+                &tld,
+                &owner,
+                &owner,
+                &test_data.min_duration,
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::PriceOutOfBounds.into()
+    );
+
+    // Negative prices are rejected even without bounds
+    e.budget().reset_default();
+    test_data
+        .contract_client
+        .set_oracle_config(&test_data.adm, &base_config);
+    let oracle: Address = create_oracle(&e, -0_2919892);
+    test_data
+        .contract_client
+        .set_oracle(&test_data.adm, &oracle);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_quote_registration(
+                &Bytes::from_slice(&e, "negative".as_bytes()),
+                &tld,
+                &test_data.min_duration,
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::PriceOutOfBounds.into()
+    );
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_oracle_config(
+                &test_data.adm,
+                &OracleConfig {
+                    min_price: 2,
+                    max_price: 1,
+                    ..base_config.clone()
+                },
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidConfig.into()
+    );
+}
+
+#[test]
+fn test_duration_config() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let renewer: Address = Address::generate(&e);
+    let domain: Bytes = Bytes::from_slice(&e, "abcde".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    let node: BytesN<32> = generate_node(&e, &domain, &tld);
+
+    test_data.col_asset_stellar.mint(&owner, &i128::MAX);
+    test_data.col_asset_stellar.mint(&renewer, &(i128::MAX / 2));
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_duration_config(
+                &test_data.adm,
+                &DurationConfig {
+                    max_duration: test_data.min_duration - 1,
+                    annual_fee: 0,
+                },
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidConfig.into()
+    );
+
+    // Domains can be registered for two years at most and they pay 10% of the collateral every year
+    let config: DurationConfig = DurationConfig {
+        max_duration: test_data.min_duration * 2,
+        annual_fee: 10_0000000,
+    };
+    test_data
+        .contract_client
+        .set_duration_config(&test_data.adm, &config);
+    assert_eq!(test_data.contract_client.duration_config(), config);
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_record(&domain, &tld, &owner, &owner, &(test_data.min_duration * 3),)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidDuration.into()
+    );
+
+    test_data.contract_client.set_record(
+        &domain,
+        &tld,
+        &owner,
+        &owner,
+        &(test_data.min_duration * 2),
+    );
+    assert_eq!(
+        test_data
+            .col_asset_client
+            .balance(&test_data.contract_client.address),
+        68_4956840
+    );
+    assert_eq!(
+        test_data.col_asset_client.balance(&test_data.fee_taker),
+        13_6991368
+    );
+
+    // The expiration date can't be extended beyond the max duration
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_renew_record(
+                &renewer,
+                &RecordKeys::Record(node.clone()),
+                &test_data.min_duration,
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidDuration.into()
+    );
+
+    e.ledger()
+        .set_timestamp(e.ledger().timestamp() + test_data.min_duration);
+    set_oracle_price(&e, 2919892);
+
+    test_data.contract_client.renew_record(
+        &renewer,
+        &RecordKeys::Record(node.clone()),
+        &test_data.min_duration,
+    );
+    assert_eq!(
+        test_data.col_asset_client.balance(&test_data.fee_taker),
+        13_6991368 + 6_8495684
+    );
+    assert_eq!(
+        test_data.col_asset_client.balance(&renewer),
+        (i128::MAX / 2) - 6_8495684
+    );
+}
+
+#[test]
+fn test_quotes() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    let node: BytesN<32> = generate_node(&e, &domain, &tld);
+    test_data.col_asset_stellar.mint(&owner, &10_000_0000000);
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_quote_registration(
+                &domain,
+                &Bytes::from_slice(&e, "eth".as_bytes()),
+                &test_data.min_duration
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::UnsupportedTLD.into()
+    );
+
+    // Quotes fail like the registration would for closed TLDs and blocked names
+    test_data
+        .contract_client
+        .set_tld_status(&test_data.adm, &tld, &TldStatus::RegistrationClosed);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_quote_registration(&domain, &tld, &test_data.min_duration)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::TldClosed.into()
+    );
+    test_data
+        .contract_client
+        .set_tld_status(&test_data.adm, &tld, &TldStatus::Active);
+
+    test_data
+        .contract_client
+        .set_blocked_name(&test_data.adm, &domain, &tld, &true);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_quote_registration(&domain, &tld, &test_data.min_duration)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::BlockedName.into()
+    );
+    test_data
+        .contract_client
+        .set_blocked_name(&test_data.adm, &domain, &tld, &false);
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_quote_renewal(&RecordKeys::Record(node.clone()), &test_data.min_duration)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::RecordDoesntExist.into()
+    );
+
+    // 20 USD with an XLM price of 0.2919892 and a 10% annual fee
+    test_data.contract_client.set_duration_config(
+        &test_data.adm,
+        &DurationConfig {
+            max_duration: 0,
+            annual_fee: 10_0000000,
+        },
+    );
+
+    let quote: RegistrationQuote =
+        test_data
+            .contract_client
+            .quote_registration(&domain, &tld, &test_data.min_duration);
+    assert_eq!(
+        quote,
+        RegistrationQuote {
+            usd_value: 20_0000000,
+            oracle_price: 0_2919892,
+            collateral: 68_4956840,
+            duration_fee: 6_8495684,
+            total: 75_3452524,
+        }
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_quote_registration(&domain, &tld, &u64::MAX)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidDuration.into()
+    );
+
+    let balance: i128 = test_data.col_asset_client.balance(&owner);
+    test_data
+        .contract_client
+        .set_record(&domain, &tld, &owner, &owner, &test_data.min_duration);
+    assert_eq!(
+        balance - test_data.col_asset_client.balance(&owner),
+        quote.total as i128
+    );
+
+    // The price of XLM goes down so the renewal takes more collateral
+    let oracle: Address = create_oracle(&e, 0_1459946);
+    test_data
+        .contract_client
+        .set_oracle(&test_data.adm, &oracle);
+
+    let quote: RenewalQuote = test_data
+        .contract_client
+        .quote_renewal(&RecordKeys::Record(node.clone()), &test_data.min_duration);
+    assert_eq!(
+        quote,
+        RenewalQuote {
+            usd_value: 20_0000000,
+            oracle_price: 0_1459946,
+            collateral: 136_9913681,
+            collateral_payment: 68_4956841,
+            collateral_refund: 0,
+            duration_fee: 13_6991369,
+            total: 82_1948210,
+            exp_date: e.ledger().timestamp() + (test_data.min_duration * 2),
+        }
+    );
+
+    let balance: i128 = test_data.col_asset_client.balance(&owner);
+    test_data.contract_client.renew_record(
+        &owner,
+        &RecordKeys::Record(node.clone()),
+        &test_data.min_duration,
+    );
+    assert_eq!(
+        balance - test_data.col_asset_client.balance(&owner),
+        quote.total as i128
+    );
+
+    let record: Domain = match test_data
+        .contract_client
+        .record(&RecordKeys::Record(node.clone()))
+        .unwrap()
+    {
+        Record::Domain(domain) => domain,
+        Record::SubDomain(_) => panic!(),
+    };
+    assert_eq!(record.exp_date, quote.exp_date);
+    assert_eq!(record.collateral, quote.collateral);
+
+    // Without a max duration, a duration long enough to overflow the annual fee is rejected
+    let oracle: Address = create_oracle(&e, 0_0000001);
+    test_data
+        .contract_client
+        .set_oracle(&test_data.adm, &oracle);
+    let duration: u64 = 3600 * 24 * 365 * 100_000_000;
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_quote_renewal(&RecordKeys::Record(node.clone()), &duration)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidDuration.into()
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_renew_record(&owner, &RecordKeys::Record(node), &duration)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidDuration.into()
+    );
+}
+
+#[test]
+fn test_rebalancing_collateral() {
+    let e: Env = create_env();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let payer: Address = Address::generate(&e);
+    let domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    let node: BytesN<32> = generate_node(&e, &domain, &tld);
+
+    test_data.col_asset_stellar.mint(&owner, &68_4956840);
+    test_data.col_asset_stellar.mint(&payer, &1_000_0000000);
+    test_data
+        .contract_client
+        .set_record(&domain, &tld, &owner, &owner, &test_data.min_duration);
+
+    // The price of XLM doubles so the domain only needs half of its collateral
+    let oracle: Address = create_oracle(&e, 0_5839784);
+    test_data
+        .contract_client
+        .set_oracle(&test_data.adm, &oracle);
+
+    assert_eq!(
+        test_data.contract_client.quote_rebalance(&node),
+        RebalanceQuote {
+            usd_value: 20_0000000,
+            oracle_price: 0_5839784,
+            collateral: 34_2478420,
+            collateral_payment: 0,
+            collateral_refund: 34_2478420,
+            undercollateralized: false,
+        }
+    );
+
+    // Only the owner can withdraw the surplus
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_rebalance_collateral(&payer, &node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NotAuthorized.into()
+    );
+    test_data
+        .contract_client
+        .rebalance_collateral(&owner, &node);
+    assert_eq!(test_data.col_asset_client.balance(&owner), 34_2478420);
+
+    let record: Domain = match test_data
+        .contract_client
+        .record(&RecordKeys::Record(node.clone()))
+        .unwrap()
+    {
+        Record::Domain(domain) => domain,
+        Record::SubDomain(_) => panic!(),
+    };
+    assert_eq!(record.collateral, 34_2478420);
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_collateral_config(
+                &test_data.adm,
+                &CollateralConfig {
+                    min_ratio: 100_0000001
+                }
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidConfig.into()
+    );
+    test_data.contract_client.set_collateral_config(
+        &test_data.adm,
+        &CollateralConfig {
+            min_ratio: 50_0000000,
+        },
+    );
+
+    // The price of XLM goes down and the domain is below 50% of the collateral it needs, so it can't be transferred
+    // until someone pays the difference
+    let oracle: Address = create_oracle(&e, 0_1459946);
+    test_data
+        .contract_client
+        .set_oracle(&test_data.adm, &oracle);
+
+    let quote: RebalanceQuote = test_data.contract_client.quote_rebalance(&node);
+    assert_eq!(quote.collateral, 136_9913681);
+    assert_eq!(quote.collateral_payment, 102_7435261);
+    assert!(quote.undercollateralized);
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_transfer(&owner, &payer, &node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::Undercollateralized.into()
+    );
+    test_data
+        .contract_client
+        .rebalance_collateral(&payer, &node);
+    assert_eq!(
+        test_data.col_asset_client.balance(&payer),
+        1_000_0000000 - 102_7435261
+    );
+    assert!(
+        !test_data
+            .contract_client
+            .quote_rebalance(&node)
+            .undercollateralized
+    );
+
+    // A balanced domain doesn't move any funds
+    test_data
+        .contract_client
+        .rebalance_collateral(&payer, &node);
+    assert_eq!(
+        test_data.col_asset_client.balance(&payer),
+        1_000_0000000 - 102_7435261
+    );
+
+    let oracle: Address = create_oracle(&e, 0_0500000);
+    test_data
+        .contract_client
+        .set_oracle(&test_data.adm, &oracle);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_transfer(&owner, &payer, &node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::Undercollateralized.into()
+    );
+
+    // Without a usable price the ratio can't be checked, so a stale oracle doesn't block the domain
+    e.ledger().set_timestamp(e.ledger().timestamp() + 901);
+    test_data.contract_client.transfer(&owner, &payer, &node);
+}
+
+#[test]
+fn test_private_tld() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let operator: Address = Address::generate(&e);
+    let owner: Address = Address::generate(&e);
+    let tld: Bytes = Bytes::from_slice(&e, "biz".as_bytes());
+    let domain: Bytes = Bytes::from_slice(&e, "averylongbusinessname".as_bytes());
+    let duration: u64 = 3600 * 24 * 30;
+
+    test_data.col_asset_stellar.mint(&owner, &i128::MAX);
+    test_data.contract_client.create_tld(
+        &test_data.adm,
+        &tld,
+        &TldConfig {
+            operator: Some(operator.clone()),
+            public: false,
+            min_duration: duration,
+            pricing_table: PricingTable {
+                length_prices: Vec::from_array(&e, [50_0000000]),
+            },
+            name_rules: NameRules {
+                min_length: 3,
+                max_length: 30,
+                allow_digits: false,
+                allow_hyphens: false,
+                allow_idn: false,
+            },
+            revenue_share: 50_0000000,
+        },
+    );
+    test_data.contract_client.set_duration_config(
+        &test_data.adm,
+        &DurationConfig {
+            max_duration: 0,
+            annual_fee: 10_0000000,
+        },
+    );
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_record(
+                &Bytes::from_slice(&e, "ab".as_bytes()),
+                &tld,
+                &owner,
+                &owner,
+                &duration,
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NameTooShort.into()
+    );
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_record(&domain, &tld, &owner, &owner, &(duration - 1))
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidDuration.into()
+    );
+
+    // Only the operator can register domains of a private TLD
+    assert!(test_data
+        .contract_client
+        .mock_auths(&[MockAuth {
+            address: &owner,
+            invoke: &MockAuthInvoke {
+                contract: &test_data.contract_client.address,
+                fn_name: "set_record",
+                args: (
+                    domain.clone(),
+                    tld.clone(),
+                    owner.clone(),
+                    owner.clone(),
+                    duration,
+                )
+                    .into_val(&e),
+                sub_invokes: &[],
+            },
+        }])
+        .try_set_record(&domain, &tld, &owner, &owner, &duration)
+        .is_err());
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_record(&domain, &tld, &owner, &owner, &duration);
+    assert!(e.auths().iter().any(|(address, _)| *address == operator));
+
+    // The collateral uses the pricing table of the TLD and the operator takes half of the annual fee
+    assert_eq!(
+        test_data
+            .col_asset_client
+            .balance(&test_data.contract_client.address),
+        171_2392102
+    );
+    assert_eq!(test_data.col_asset_client.balance(&operator), 7037228);
+    assert_eq!(
+        test_data.col_asset_client.balance(&test_data.fee_taker),
+        7037228
+    );
+}
+
+#[test]
+fn test_tld_lifecycle() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let buyer: Address = Address::generate(&e);
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    let domain: Bytes = Bytes::from_slice(&e, "abcde".as_bytes());
+    let node: BytesN<32> = generate_node(&e, &domain, &tld);
+    let year: u64 = test_data.min_duration;
+
+    test_data.col_asset_stellar.mint(&owner, &10_000_0000000);
+    test_data.col_asset_stellar.mint(&buyer, &10_000_0000000);
+    test_data
+        .contract_client
+        .set_record(&domain, &tld, &owner, &owner, &(year * 2));
+
+    // Existing domains can be renewed and traded but new domains can't be registered
+    test_data
+        .contract_client
+        .set_tld_status(&test_data.adm, &tld, &TldStatus::RegistrationClosed);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_record(
+                &Bytes::from_slice(&e, "other".as_bytes()),
+                &tld,
+                &owner,
+                &owner,
+                &year,
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::TldClosed.into()
+    );
+    test_data
+        .contract_client
+        .renew_record(&owner, &RecordKeys::Record(node.clone()), &year);
+    test_data
+        .contract_client
+        .set_offer(&buyer, &node, &100_0000000);
+
+    // Domains can't be renewed beyond the sunset date and new offers are rejected
+    let sunset_date: u64 = e.ledger().timestamp() + (year * 3) + 100;
+    test_data
+        .contract_client
+        .set_tld_status(&test_data.adm, &tld, &TldStatus::Sunset(sunset_date));
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_renew_record(&owner, &RecordKeys::Record(node.clone()), &year)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidDuration.into()
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_offer(&owner, &node, &200_0000000)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::TldClosed.into()
+    );
+
+    // The domain keeps resolving but the offers set before the sunset can't be taken, the buyer can only cancel them
+    match test_data
+        .contract_client
+        .record(&RecordKeys::Record(node.clone()))
+    {
+        Some(Record::Domain(domain)) => {
+            assert_eq!(domain.exp_date, sunset_date - 100);
+        }
+        _ => panic!("The domain should exist"),
+    }
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_take_offer(&owner, &node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::TldClosed.into()
+    );
+    test_data
+        .contract_client
+        .burn_offer(&OffersDataKeys::BuyOffer(node.clone()));
+    assert_eq!(test_data.col_asset_client.balance(&buyer), 10_000_0000000);
+}
+
+#[test]
+fn test_updating_address() {
+    let e: Env = create_env();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let address: Address = Address::generate(&e);
+    let second_address: Address = Address::generate(&e);
+
+    let new_domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+
+    test_data.col_asset_stellar.mock_all_auths().mint(
+        &owner,
+        &(test_data.min_duration as i128 * test_data.node_rate as i128),
+    );
+
+    test_data.contract_client.mock_all_auths().set_record(
+        &new_domain,
+        &tld,
+        &owner,
+        &address,
+        &test_data.min_duration,
+    );
+
+    let node: BytesN<32> = test_data.contract_client.parse_domain(&new_domain, &tld);
+
+    let first_record: Domain = match test_data
+        .contract_client
+        .record(&RecordKeys::Record(node.clone()))
+        .unwrap()
+    {
+        Record::Domain(domain) => domain,
+        Record::SubDomain(_) => panic!(),
+    };
+
+    test_data
+        .contract_client
+        .mock_auths(&[MockAuth {
+            address: &owner,
+            invoke: &MockAuthInvoke {
+                contract: &test_data.contract_client.address,
+                fn_name: "update_address",
+                args: (RecordKeys::Record(node.clone()), second_address.clone()).into_val(&e),
+                sub_invokes: &[],
+            },
+        }])
+        .update_address(&RecordKeys::Record(node.clone()), &second_address);
+
+    let second_record: Domain = match test_data
+        .contract_client
+        .record(&RecordKeys::Record(node.clone()))
+        .unwrap()
+    {
+        Record::Domain(domain) => domain,
+        Record::SubDomain(_) => panic!(),
+    };
+
+    assert_eq!(address, first_record.address);
+    assert_eq!(second_address, second_record.address);
+}
+
+#[test]
+fn test_commit_reveal() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+
+    // The oracle entries of the snapshot are archived after its ledger, so we go back a few ledgers to have room for
+    // the delays of the commitments
+    e.ledger().with_mut(|ledger| ledger.sequence_number -= 20);
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    let secret: BytesN<32> = BytesN::from_array(&e, &[7; 32]);
+    test_data.col_asset_stellar.mint(&owner, &10_000_0000000);
+
+    // The min delay needs to be at least one ledger and lower than the max age
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_commitments_config(
+                &test_data.adm,
+                &CommitmentsConfig {
+                    required: true,
+                    min_delay: 0,
+                    max_age: 10,
+                },
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidConfig.into()
+    );
+
+    let config: CommitmentsConfig = CommitmentsConfig {
+        required: true,
+        min_delay: 2,
+        max_age: 10,
+    };
+    test_data
+        .contract_client
+        .set_commitments_config(&test_data.adm, &config);
+    assert_eq!(test_data.contract_client.commitments_config(), config);
+
+    let domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_record(&domain, &tld, &owner, &owner, &test_data.min_duration)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::CommitmentRequired.into()
+    );
+
+    let commitment: BytesN<32> = test_data
+        .contract_client
+        .commitment_hash(&domain, &tld, &owner, &secret);
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_reveal_and_register(
+                &domain,
+                &tld,
+                &owner,
+                &owner,
+                &test_data.min_duration,
+                &secret,
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::CommitmentNotFound.into()
+    );
+
+    test_data.contract_client.commit(&commitment);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_commit(&commitment)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::CommitmentAlreadyExists.into()
+    );
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_reveal_and_register(
+                &domain,
+                &tld,
+                &owner,
+                &owner,
+                &test_data.min_duration,
+                &secret,
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::CommitmentTooRecent.into()
+    );
+
+    e.ledger().with_mut(|ledger| ledger.sequence_number += 2);
+
+    // The commitment is bound to the owner, someone who saw the reveal can't use it for another address
+    let other: Address = Address::generate(&e);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_reveal_and_register(
+                &domain,
+                &tld,
+                &other,
+                &other,
+                &test_data.min_duration,
+                &secret,
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::CommitmentNotFound.into()
+    );
+
+    test_data.contract_client.reveal_and_register(
+        &domain,
+        &tld,
+        &owner,
+        &owner,
+        &test_data.min_duration,
+        &secret,
+    );
+
+    let node: BytesN<32> = generate_node(&e, &domain, &tld);
+    match test_data
+        .contract_client
+        .record(&RecordKeys::Record(node))
+        .unwrap()
+    {
+        Record::Domain(record) => assert_eq!(record.owner, owner),
+        Record::SubDomain(_) => panic!(),
+    }
+
+    // The commitment is removed once it's used
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_reveal_and_register(
+                &domain,
+                &tld,
+                &owner,
+                &owner,
+                &test_data.min_duration,
+                &secret,
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::CommitmentNotFound.into()
+    );
+
+    // Commitments older than the max age can't be revealed, but they can be made again
+    let domain: Bytes = Bytes::from_slice(&e, "soroban".as_bytes());
+    let commitment: BytesN<32> = test_data
+        .contract_client
+        .commitment_hash(&domain, &tld, &owner, &secret);
+    test_data.contract_client.commit(&commitment);
+    e.ledger().with_mut(|ledger| ledger.sequence_number += 11);
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_reveal_and_register(
+                &domain,
+                &tld,
+                &owner,
+                &owner,
+                &test_data.min_duration,
+                &secret,
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::CommitmentExpired.into()
+    );
+
+    test_data.contract_client.commit(&commitment);
+    e.ledger().with_mut(|ledger| ledger.sequence_number += 2);
+    test_data.contract_client.reveal_and_register(
+        &domain,
+        &tld,
+        &owner,
+        &owner,
+        &test_data.min_duration,
+        &secret,
+    );
+}
+
+#[test]
+fn test_renewing_record() {
+    let e: Env = create_env();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let payer: Address = Address::generate(&e);
+    let domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+
+    test_data
+        .col_asset_stellar
+        .mock_all_auths()
+        .mint(&owner, &68_4956840i128);
+
+    test_data.contract_client.mock_all_auths().set_record(
+        &domain,
+        &tld,
+        &owner,
+        &owner,
+        &test_data.min_duration,
+    );
+
+    let node: BytesN<32> = test_data.contract_client.parse_domain(&domain, &tld);
+
+    e.ledger().set_timestamp(1742825701 + 10);
+
+    // The payer is not the owner of the domain, and it doesn't need to be
+    test_data
+        .contract_client
+        .mock_auths(&[MockAuth {
+            address: &payer,
+            invoke: &MockAuthInvoke {
+                contract: &test_data.contract_client.address,
+                fn_name: "renew_record",
+                args: (
+                    payer.clone(),
+                    RecordKeys::Record(node.clone()),
+                    test_data.min_duration,
+                )
+                    .into_val(&e),
+                sub_invokes: &[],
+            },
+        }])
+        .renew_record(
+            &payer,
+            &RecordKeys::Record(node.clone()),
+            &test_data.min_duration,
+        );
+
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                test_data.contract_client.address.clone(),
+                (EventTopics::RecordRenewed, node.clone()).into_val(&e),
+                (
+                    payer.clone(),
+                    1742825701 + (test_data.min_duration * 2),
+                    68_4956840u128,
+                    1742825701u64 + 10,
+                )
+                    .into_val(&e),
+            )
+        ]
+    );
+
+    let renewed_record: Domain = match test_data
+        .contract_client
+        .record(&RecordKeys::Record(node.clone()))
+        .unwrap()
+    {
+        Record::Domain(domain) => domain,
+        Record::SubDomain(_) => panic!(),
+    };
+
+    // The oracle price didn't change so the collateral is the same and the snapshot is never updated
+    assert_eq!(
+        renewed_record,
+        Domain {
+            node: node.clone(),
+            owner: owner.clone(),
+            address: owner.clone(),
+            exp_date: 1742825701 + (test_data.min_duration * 2),
+            collateral: 68_4956840u128,
+            snapshot: 1742825701,
+        }
+    );
+
+    let invalid_duration_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_renew_record(
+            &payer,
+            &RecordKeys::Record(node.clone()),
+            &(test_data.min_duration - 1),
+        )
+        .unwrap_err()
+        .unwrap();
+
+    assert_eq!(
+        invalid_duration_error,
+        ContractErrors::InvalidDuration.into()
+    );
+
+    let missing_record_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_renew_record(
+            &payer,
+            &RecordKeys::Record(BytesN::from_array(&e, &[0; 32])),
+            &test_data.min_duration,
+        )
+        .unwrap_err()
+        .unwrap();
+
+    assert_eq!(
+        missing_record_error,
+        ContractErrors::RecordDoesntExist.into()
+    );
+}
+
+#[test]
+fn test_registering_expired_domain() {
+    let e: Env = create_env();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let first_owner: Address = Address::generate(&e);
+    let second_owner: Address = Address::generate(&e);
+    let buyer: Address = Address::generate(&e);
+    let domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    let grace_period: u64 = 3600 * 24 * 30;
+
+    test_data
+        .col_asset_stellar
+        .mock_all_auths()
+        .mint(&first_owner, &68_4956840i128);
+
+    test_data
+        .col_asset_stellar
+        .mock_all_auths()
+        .mint(&second_owner, &68_4956840i128);
+
+    test_data
+        .col_asset_stellar
+        .mock_all_auths()
+        .mint(&buyer, &100_0000000i128);
+
+    // 10% of the old collateral goes to the fee taker
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_expiration_config(&test_data.adm, &grace_period, &10_0000000);
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .mock_all_auths()
+            .try_set_expiration_config(&test_data.adm, &grace_period, &100_0000001)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidConfig.into()
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .mock_all_auths()
+            .try_set_expiration_config(&test_data.adm, &(MAX_GRACE_PERIOD + 1), &10_0000000)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidConfig.into()
+    );
+
+    test_data.contract_client.mock_all_auths().set_record(
+        &domain,
+        &tld,
+        &first_owner,
+        &first_owner,
+        &test_data.min_duration,
+    );
+
+    let node: BytesN<32> = test_data.contract_client.parse_domain(&domain, &tld);
+
+    // Offers left on the old record are canceled once someone else registers the domain
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_offer(&buyer, &node, &100_0000000);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_offer(&first_owner, &node, &200_0000000);
+
+    // During the grace period the domain can't be claimed and only the owner can renew it
+    e.ledger()
+        .set_timestamp(1742825701 + test_data.min_duration + grace_period);
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .mock_all_auths()
+            .try_set_record(
+                &domain,
+                &tld,
+                &second_owner,
+                &second_owner,
+                &test_data.min_duration,
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::RecordAlreadyExist.into()
+    );
+
+    assert!(test_data
+        .contract_client
+        .mock_auths(&[MockAuth {
+            address: &second_owner,
+            invoke: &MockAuthInvoke {
+                contract: &test_data.contract_client.address,
+                fn_name: "renew_record",
+                args: (
+                    second_owner.clone(),
+                    RecordKeys::Record(node.clone()),
+                    test_data.min_duration,
+                )
+                    .into_val(&e),
+                sub_invokes: &[],
+            },
+        }])
+        .try_renew_record(
+            &second_owner,
+            &RecordKeys::Record(node.clone()),
+            &test_data.min_duration,
+        )
+        .is_err());
+
+    // Once the grace period is over, the domain can't be renewed anymore, but anyone can register it again
+    e.ledger()
+        .set_timestamp(1742825701 + test_data.min_duration + grace_period + 1);
+    set_oracle_price(&e, 2919892);
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .mock_all_auths()
+            .try_renew_record(
+                &first_owner,
+                &RecordKeys::Record(node.clone()),
+                &test_data.min_duration,
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::ExpiredDomain.into()
+    );
+
+    test_data.contract_client.mock_all_auths().set_record(
+        &domain,
+        &tld,
+        &second_owner,
+        &second_owner,
+        &test_data.min_duration,
+    );
+
+    let new_record: Domain = match test_data
+        .contract_client
+        .record(&RecordKeys::Record(node.clone()))
+        .unwrap()
+    {
+        Record::Domain(domain) => domain,
+        Record::SubDomain(_) => panic!(),
+    };
+
+    assert_eq!(new_record.owner, second_owner);
+    assert_eq!(
+        new_record.snapshot,
+        1742825701 + test_data.min_duration + grace_period + 1
+    );
+
+    assert_eq!(
+        test_data.col_asset_client.balance(&first_owner),
+        68_4956840i128 - 6_8495684
+    );
+    assert_eq!(
+        test_data.col_asset_client.balance(&test_data.fee_taker),
+        6_8495684
+    );
+    assert_eq!(test_data.col_asset_client.balance(&buyer), 100_0000000);
+    assert_eq!(
+        test_data
+            .col_asset_client
+            .balance(&test_data.contract_client.address),
+        68_4956840i128
+    );
+
+    for key in [
+        OffersDataKeys::BuyOffer(node.clone()),
+        OffersDataKeys::SaleOffer(node.clone()),
+    ] {
+        assert_eq!(
+            test_data
+                .contract_client
+                .mock_all_auths()
+                .try_burn_offer(&key)
+                .unwrap_err()
+                .unwrap(),
+            ContractErrors::OfferDoesntExist.into()
+        );
+    }
+}
+
+#[test]
+fn test_liquidating_expired_domain() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let buyer: Address = Address::generate(&e);
+    let keeper: Address = Address::generate(&e);
+    let domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    let grace_period: u64 = 3600 * 24 * 30;
+
+    test_data.col_asset_stellar.mint(&owner, &68_4956840i128);
+    test_data.col_asset_stellar.mint(&buyer, &100_0000000i128);
+
+    // 10% of the collateral goes to the fee taker and 5% (the default reward) to the keeper
+    test_data
+        .contract_client
+        .set_expiration_config(&test_data.adm, &grace_period, &10_0000000);
+    assert_eq!(
+        test_data.contract_client.liquidation_config(),
+        LiquidationConfig {
+            keeper_reward: 5_0000000
+        }
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_liquidation_config(
+                &test_data.adm,
+                &LiquidationConfig {
+                    keeper_reward: 100_0000001
+                }
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidConfig.into()
+    );
+
+    test_data
+        .contract_client
+        .set_record(&domain, &tld, &owner, &owner, &test_data.min_duration);
+    let node: BytesN<32> = test_data.contract_client.parse_domain(&domain, &tld);
+    let sub: Bytes = Bytes::from_slice(&e, "sub".as_bytes());
+    let sub_node: BytesN<32> = generate_node(&e, &sub, &Bytes::from(node.clone()));
+    test_data
+        .contract_client
+        .set_sub(&sub, &RecordKeys::Record(node.clone()), &owner);
+    test_data
+        .contract_client
+        .set_offer(&buyer, &node, &100_0000000);
+
+    // The domain can't be liquidated before the end of the grace period
+    for timestamp in [
+        1742825701,
+        1742825701 + test_data.min_duration + grace_period,
+    ] {
+        e.ledger().set_timestamp(timestamp);
+        assert_eq!(
+            test_data
+                .contract_client
+                .try_liquidate(&keeper, &node)
+                .unwrap_err()
+                .unwrap(),
+            ContractErrors::NotLiquidatable.into()
+        );
+    }
+
+    e.ledger()
+        .set_timestamp(1742825701 + test_data.min_duration + grace_period + 1);
+
+    // Once the domain can be liquidated the owner can't sell it, transfer it or burn it to get the full collateral back
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_take_offer(&owner, &node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::ExpiredDomain.into()
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_transfer(&owner, &buyer, &node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::ExpiredDomain.into()
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_burn_record(&RecordKeys::Record(node.clone()))
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::ExpiredDomain.into()
+    );
+
+    test_data.contract_client.liquidate(&keeper, &node);
+
+    assert_eq!(
+        test_data.col_asset_client.balance(&test_data.fee_taker),
+        6_8495684
+    );
+    assert_eq!(test_data.col_asset_client.balance(&keeper), 3_4247842);
+    assert_eq!(test_data.col_asset_client.balance(&owner), 58_2213314);
+    assert_eq!(test_data.col_asset_client.balance(&buyer), 100_0000000);
+    assert_eq!(
+        test_data
+            .col_asset_client
+            .balance(&test_data.contract_client.address),
+        0
+    );
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .record(&RecordKeys::Record(node.clone())),
+        None
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .record(&RecordKeys::SubRecord(sub_node)),
+        None
+    );
+    assert_eq!(test_data.contract_client.domain_count(&owner), 0);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_liquidate(&keeper, &node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::RecordDoesntExist.into()
+    );
+}
+
+#[test]
+fn test_records_events() {
+    let e: Env = create_env();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let new_owner: Address = Address::generate(&e);
+    let domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    let node: BytesN<32> = generate_node(&e, &domain, &tld);
+
+    test_data
+        .col_asset_stellar
+        .mock_all_auths()
+        .mint(&owner, &68_4956840i128);
+
+    test_data.contract_client.mock_all_auths().set_record(
+        &domain,
+        &tld,
+        &owner,
+        &owner,
+        &test_data.min_duration,
+    );
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .transfer(&owner, &new_owner, &node);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .burn_record(&RecordKeys::Record(node.clone()));
+
+    let mut filtered_events = Vec::new(&e);
+    for (contract_id, topics, data) in e.events().all().iter() {
+        if contract_id == test_data.contract_client.address {
+            filtered_events.push_back((topics.clone(), data.clone()));
+        }
+    }
+
+    // The first events are the config events emitted when the contract was initialized
+    assert_eq!(
+        filtered_events.slice(3..),
+        vec![
+            &e,
+            (
+                (EventTopics::RecordSet, node.clone()).into_val(&e),
+                (
+                    domain.clone(),
+                    tld.clone(),
+                    owner.clone(),
+                    owner.clone(),
+                    1742825701 + test_data.min_duration,
+                    68_4956840u128,
+                    1742825701u64,
+                )
+                    .into_val(&e)
+            ),
+            (
+                (EventTopics::RecordTransferred, node.clone()).into_val(&e),
+                (owner.clone(), new_owner.clone(), 1742825701u64).into_val(&e)
+            ),
+            (
+                (EventTopics::RecordBurned, node.clone()).into_val(&e),
+                (new_owner.clone(), 68_4956840u128, 1742825701u64).into_val(&e)
+            ),
+        ]
+    );
 }
@@ -1,9 +1,13 @@
 #![cfg(test)]
 
+use crate::errors::ContractErrors;
+use crate::storage::approvals::Approval;
+use crate::storage::core::NftConfig;
 use crate::storage::record::{Domain, Record, RecordKeys};
 use crate::tests::test_utils::{create_test_data, init_contract, TestData};
+use crate::utils::nft::TokenMetadata;
 use soroban_sdk::testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke};
-use soroban_sdk::{Address, Bytes, BytesN, Env, IntoVal};
+use soroban_sdk::{Address, Bytes, BytesN, Env, IntoVal, String, Vec};
 use test_utils::create_env;
 
 #[test]
@@ -51,11 +55,11 @@ fn test_simple_transfer() {
             invoke: &MockAuthInvoke {
                 contract: &test_data.contract_client.address,
                 fn_name: "transfer",
-                args: (RecordKeys::Record(node.clone()), second_owner.clone()).into_val(&e),
+                args: (first_owner.clone(), second_owner.clone(), node.clone()).into_val(&e),
                 sub_invokes: &[],
             },
         }])
-        .transfer(&RecordKeys::Record(node.clone()), &second_owner);
+        .transfer(&first_owner, &second_owner, &node);
 
     let second_record: Domain = match test_data
         .contract_client
@@ -72,17 +76,420 @@ fn test_simple_transfer() {
     assert_eq!(1742825701 + 10, second_record.snapshot);
 
     // It should fail because first owner is no the owner anymore
+    assert_eq!(
+        test_data
+            .contract_client
+            .mock_auths(&[MockAuth {
+                address: &first_owner,
+                invoke: &MockAuthInvoke {
+                    contract: &test_data.contract_client.address,
+                    fn_name: "transfer",
+                    args: (first_owner.clone(), second_owner.clone(), node.clone()).into_val(&e),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_transfer(&first_owner, &second_owner, &node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NotAuthorized.into()
+    );
+
+    // Only domains are tokens, any other node fails like a missing record
+    assert_eq!(
+        test_data
+            .contract_client
+            .mock_all_auths()
+            .try_transfer(
+                &second_owner,
+                &first_owner,
+                &BytesN::from_array(&e, &[0; 32])
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::RecordDoesntExist.into()
+    );
+}
+
+#[test]
+fn test_approvals() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let spender: Address = Address::generate(&e);
+    let operator: Address = Address::generate(&e);
+    let buyer: Address = Address::generate(&e);
+    let domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+
+    test_data.col_asset_stellar.mint(&owner, &10_000_0000000);
+    test_data
+        .contract_client
+        .set_record(&domain, &tld, &owner, &owner, &test_data.min_duration);
+    let node: BytesN<32> = test_data.contract_client.parse_domain(&domain, &tld);
+
+    // Only the owner or an operator can approve and only approved addresses can use `transfer_from`
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_approve(&spender, &spender, &node, &(e.ledger().sequence() + 100))
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NotAuthorized.into()
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_approve(&owner, &spender, &node, &(e.ledger().sequence() - 1))
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidExpiry.into()
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_transfer_from(&spender, &owner, &spender, &node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NotAuthorized.into()
+    );
+
+    let live_until_ledger: u32 = e.ledger().sequence() + 100;
+    test_data
+        .contract_client
+        .approve(&owner, &spender, &node, &live_until_ledger);
+    assert_eq!(
+        test_data.contract_client.approval(&node),
+        Some(Approval {
+            spender: spender.clone(),
+            live_until_ledger,
+            snapshot: e.ledger().timestamp(),
+        })
+    );
+    assert_eq!(
+        test_data.contract_client.get_approved(&node),
+        Some(spender.clone())
+    );
+
+    // The approval is valid until its last ledger (included)
+    e.ledger().set_sequence_number(live_until_ledger);
+    assert!(test_data.contract_client.approval(&node).is_some());
+
+    // Expired approvals can't be used
+    e.ledger().set_sequence_number(live_until_ledger + 1);
+    assert_eq!(test_data.contract_client.approval(&node), None);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_transfer_from(&spender, &owner, &spender, &node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NotAuthorized.into()
+    );
+
+    let live_until_ledger: u32 = e.ledger().sequence() + 100;
+    test_data
+        .contract_client
+        .approve(&owner, &spender, &node, &live_until_ledger);
+
+    // Revoked approvals can't be used
+    test_data.contract_client.revoke_approval(&owner, &node);
+    assert_eq!(test_data.contract_client.approval(&node), None);
+
+    test_data
+        .contract_client
+        .approve(&owner, &spender, &node, &live_until_ledger);
+    e.ledger().set_timestamp(e.ledger().timestamp() + 10);
+
+    // `from` needs to be the owner of the domain
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_transfer_from(&spender, &buyer, &spender, &node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NotAuthorized.into()
+    );
+    test_data
+        .contract_client
+        .transfer_from(&spender, &owner, &spender, &node);
+
+    let record: Domain = match test_data
+        .contract_client
+        .record(&RecordKeys::Record(node.clone()))
+        .unwrap()
+    {
+        Record::Domain(domain) => domain,
+        Record::SubDomain(_) => panic!(),
+    };
+    assert_eq!(record.owner, spender);
+
+    // The approval is cleared by the transfer, so the spender can't use it again once the domain goes back to the owner
+    assert_eq!(test_data.contract_client.approval(&node), None);
+    test_data.contract_client.transfer(&spender, &owner, &node);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_transfer_from(&spender, &owner, &spender, &node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NotAuthorized.into()
+    );
+
+    // Operators can transfer and approve every domain of the owner until they are removed
+    test_data
+        .contract_client
+        .set_approval_for_all(&owner, &operator, &true);
     assert!(test_data
         .contract_client
-        .mock_auths(&[MockAuth {
-            address: &first_owner,
-            invoke: &MockAuthInvoke {
-                contract: &test_data.contract_client.address,
-                fn_name: "transfer",
-                args: (RecordKeys::Record(node.clone()), second_owner.clone()).into_val(&e),
-                sub_invokes: &[],
-            },
-        }])
-        .try_transfer(&RecordKeys::Record(node.clone()), &second_owner)
+        .is_approved_for_all(&owner, &operator));
+    test_data
+        .contract_client
+        .approve(&operator, &spender, &node, &live_until_ledger);
+    assert_eq!(
+        test_data.contract_client.approval(&node).unwrap().spender,
+        spender
+    );
+
+    test_data
+        .contract_client
+        .set_approval_for_all(&owner, &operator, &false);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_transfer_from(&operator, &owner, &buyer, &node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NotAuthorized.into()
+    );
+
+    test_data
+        .contract_client
+        .set_approval_for_all(&owner, &operator, &true);
+    test_data
+        .contract_client
+        .transfer_from(&operator, &owner, &buyer, &node);
+
+    let record: Domain = match test_data
+        .contract_client
+        .record(&RecordKeys::Record(node.clone()))
+        .unwrap()
+    {
+        Record::Domain(domain) => domain,
+        Record::SubDomain(_) => panic!(),
+    };
+    assert_eq!(record.owner, buyer);
+
+    // The operator of the old owner can't transfer the domain anymore
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_transfer_from(&operator, &buyer, &owner, &node)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NotAuthorized.into()
+    );
+}
+
+#[test]
+fn test_nft_interface() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let buyer: Address = Address::generate(&e);
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+    let first_domain: Bytes = Bytes::from_slice(&e, "stellar".as_bytes());
+    let second_domain: Bytes = Bytes::from_slice(&e, "soroban".as_bytes());
+
+    test_data.col_asset_stellar.mint(&owner, &10_000_0000000);
+    for domain in [first_domain.clone(), second_domain.clone()] {
+        test_data.contract_client.set_record(
+            &domain,
+            &tld,
+            &owner,
+            &owner,
+            &test_data.min_duration,
+        );
+    }
+    let first_node: BytesN<32> = test_data.contract_client.parse_domain(&first_domain, &tld);
+    let second_node: BytesN<32> = test_data.contract_client.parse_domain(&second_domain, &tld);
+
+    assert_eq!(
+        test_data.contract_client.name(),
+        String::from_str(&e, "Soroban Domains")
+    );
+    assert_eq!(
+        test_data.contract_client.symbol(),
+        String::from_str(&e, "DOMAIN")
+    );
+    assert_eq!(test_data.contract_client.balance(&owner), 2);
+    assert_eq!(test_data.contract_client.balance(&buyer), 0);
+    assert_eq!(test_data.contract_client.owner_of(&first_node), owner);
+    assert_eq!(test_data.contract_client.token_uri(&first_node), None);
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_owner_of(&BytesN::from_array(&e, &[0; 32]))
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::RecordDoesntExist.into()
+    );
+
+    // Only the roles with access can update the metadata of the collection and the base URI has a max length
+    let config: NftConfig = NftConfig {
+        name: String::from_str(&e, "Stellar Names"),
+        symbol: String::from_str(&e, "NAME"),
+        base_uri: String::from_str(&e, "https://example.com/nft/"),
+    };
+    assert!(test_data
+        .contract_client
+        .try_set_nft_config(&owner, &config)
         .is_err());
+    assert_eq!(
+        test_data
+            .contract_client
+            .try_set_nft_config(
+                &test_data.adm,
+                &NftConfig {
+                    base_uri: String::from_bytes(&e, &[b'a'; 129]),
+                    ..config.clone()
+                }
+            )
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InvalidConfig.into()
+    );
+    test_data
+        .contract_client
+        .set_nft_config(&test_data.adm, &config);
+    assert_eq!(test_data.contract_client.name(), config.name);
+    assert_eq!(test_data.contract_client.symbol(), config.symbol);
+
+    let uri: String = String::from_str(
+        &e,
+        "https://example.com/nft/2fe4cc6a15f9466bad71ed407a8f1b7da81efd931e7712753152aa17abc0e06e",
+    );
+    assert_eq!(
+        test_data.contract_client.token_uri(&first_node),
+        Some(uri.clone())
+    );
+
+    let metadata: TokenMetadata = test_data.contract_client.token_metadata(&first_node);
+    assert_eq!(
+        metadata.name,
+        Some(Bytes::from_slice(&e, "stellar.xlm".as_bytes()))
+    );
+    assert_eq!(metadata.owner, owner);
+    assert_eq!(metadata.uri, Some(uri));
+
+    // Balances follow the transfers and the burns of the domains
+    test_data
+        .contract_client
+        .approve(&owner, &buyer, &first_node, &(e.ledger().sequence() + 100));
+    assert_eq!(
+        test_data.contract_client.get_approved(&first_node),
+        Some(buyer.clone())
+    );
+
+    e.ledger().set_timestamp(e.ledger().timestamp() + 10);
+    test_data
+        .contract_client
+        .transfer_from(&buyer, &owner, &buyer, &first_node);
+    assert_eq!(test_data.contract_client.owner_of(&first_node), buyer);
+    assert_eq!(test_data.contract_client.get_approved(&first_node), None);
+    assert_eq!(test_data.contract_client.balance(&owner), 1);
+    assert_eq!(test_data.contract_client.balance(&buyer), 1);
+
+    test_data
+        .contract_client
+        .burn_record(&RecordKeys::Record(second_node));
+    assert_eq!(test_data.contract_client.balance(&owner), 0);
+}
+
+#[test]
+fn test_owner_index() {
+    let e: Env = create_env();
+    e.mock_all_auths();
+    let test_data: TestData = create_test_data(&e);
+    init_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let buyer: Address = Address::generate(&e);
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+
+    test_data.col_asset_stellar.mint(&owner, &10_000_0000000);
+    test_data.col_asset_stellar.mint(&buyer, &10_000_0000000);
+
+    let mut nodes: Vec<BytesN<32>> = Vec::new(&e);
+    for domain in ["stellar", "soroban", "lumens"] {
+        let domain: Bytes = Bytes::from_slice(&e, domain.as_bytes());
+        test_data.contract_client.set_record(
+            &domain,
+            &tld,
+            &owner,
+            &owner,
+            &test_data.min_duration,
+        );
+        nodes.push_back(test_data.contract_client.parse_domain(&domain, &tld));
+    }
+
+    assert_eq!(test_data.contract_client.domain_count(&owner), 3);
+    assert_eq!(test_data.contract_client.domains_of(&owner, &0, &10), nodes);
+    assert_eq!(
+        test_data.contract_client.domains_of(&owner, &1, &1),
+        Vec::from_array(&e, [nodes.get(1).unwrap()])
+    );
+    assert_eq!(
+        test_data.contract_client.domains_of(&owner, &3, &10).len(),
+        0
+    );
+    assert_eq!(
+        test_data.contract_client.domains_of(&buyer, &0, &10).len(),
+        0
+    );
+
+    // The last domain takes the position of the transferred one
+    e.ledger().set_timestamp(e.ledger().timestamp() + 10);
+    test_data
+        .contract_client
+        .transfer(&owner, &buyer, &nodes.get(0).unwrap());
+    assert_eq!(
+        test_data.contract_client.domains_of(&owner, &0, &10),
+        Vec::from_array(&e, [nodes.get(2).unwrap(), nodes.get(1).unwrap()])
+    );
+    assert_eq!(
+        test_data.contract_client.domains_of(&buyer, &0, &10),
+        Vec::from_array(&e, [nodes.get(0).unwrap()])
+    );
+
+    test_data
+        .contract_client
+        .burn_record(&RecordKeys::Record(nodes.get(1).unwrap()));
+    assert_eq!(
+        test_data.contract_client.domains_of(&owner, &0, &10),
+        Vec::from_array(&e, [nodes.get(2).unwrap()])
+    );
+
+    // Sold domains move to the list of the buyer
+    test_data
+        .contract_client
+        .set_offer(&owner, &nodes.get(2).unwrap(), &1_000_0000000);
+    test_data
+        .contract_client
+        .take_offer(&buyer, &nodes.get(2).unwrap());
+    assert_eq!(test_data.contract_client.domain_count(&owner), 0);
+    assert_eq!(
+        test_data.contract_client.domains_of(&owner, &0, &10).len(),
+        0
+    );
+    assert_eq!(
+        test_data.contract_client.domains_of(&buyer, &0, &10),
+        Vec::from_array(&e, [nodes.get(0).unwrap(), nodes.get(2).unwrap()])
+    );
+    assert_eq!(test_data.contract_client.balance(&buyer), 2);
 }
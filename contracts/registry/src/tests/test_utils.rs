@@ -2,7 +2,11 @@
 
 use crate::contract::{RegistryContract, RegistryContractClient};
 use soroban_sdk::testutils::Address as _;
-use soroban_sdk::{token, Address, Bytes, Env, String, Vec};
+use soroban_sdk::{symbol_short, token, Address, Bytes, Env, String, Vec};
+
+mod oracle {
+    soroban_sdk::contractimport!(file = "../../oracle.wasm");
+}
 
 fn create_token_contract<'a>(
     e: &Env,
@@ -81,7 +85,7 @@ pub fn init_contract(e: &Env, test_data: &TestData) {
     test_data
         .contract_client
         .mock_all_auths()
-        .set_offers_config(&test_data.fee_taker, &test_data.offer_fee);
+        .set_offers_config(&test_data.adm, &test_data.fee_taker, &test_data.offer_fee);
 
     let oracle_addr: Address = Address::from_string(&String::from_str(
         e,
@@ -90,5 +94,56 @@ pub fn init_contract(e: &Env, test_data: &TestData) {
     test_data
         .contract_client
         .mock_all_auths()
-        .set_oracle(&oracle_addr);
+        .set_oracle(&test_data.adm, &oracle_addr);
+}
+
+// Updates the XLM price (with 7 decimals) in the oracle at the current ledger timestamp
+// This is useful for tests that move the ledger time, because the oracle doesn't return old prices
+pub fn set_oracle_price(e: &Env, price: i128) {
+    let oracle_client: oracle::Client = oracle::Client::new(
+        e,
+        &Address::from_string(&String::from_str(
+            e,
+            "CAFJZQWSED6YAWZU3GWRTOCNPPCGBN32L7QV43XX5LZLFTK6JLN34DLN",
+        )),
+    );
+
+    let decimals: u32 = oracle_client.decimals();
+    let resolution: u64 = oracle_client.resolution() as u64;
+    let mut updates: Vec<i128> = Vec::new(e);
+    for asset in oracle_client.assets().iter() {
+        if asset == oracle::Asset::Other(symbol_short!("XLM")) {
+            updates.push_back(price * 10i128.pow(decimals - 7));
+        } else {
+            updates.push_back(0);
+        }
+    }
+
+    let timestamp: u64 = e.ledger().timestamp() - (e.ledger().timestamp() % resolution);
+    oracle_client
+        .mock_all_auths()
+        .set_price(&updates, &(timestamp * 1000));
+}
+
+// Creates a new oracle with the XLM price (with 7 decimals) at the current ledger timestamp
+pub fn create_oracle(e: &Env, price: i128) -> Address {
+    let oracle_client: oracle::Client =
+        oracle::Client::new(e, &e.register_contract_wasm(None, oracle::WASM));
+
+    oracle_client.mock_all_auths().config(&oracle::ConfigData {
+        admin: Address::generate(e),
+        assets: Vec::from_array(e, [oracle::Asset::Other(symbol_short!("XLM"))]),
+        base_asset: oracle::Asset::Other(symbol_short!("USD")),
+        decimals: 14,
+        period: 86_400_000,
+        resolution: 300_000,
+    });
+
+    let timestamp: u64 = e.ledger().timestamp() - (e.ledger().timestamp() % 300);
+    oracle_client.mock_all_auths().set_price(
+        &Vec::from_array(e, [price * 10i128.pow(7)]),
+        &(timestamp * 1000),
+    );
+
+    oracle_client.address
 }
@@ -0,0 +1,196 @@
+use crate::errors::ContractErrors;
+use crate::storage::tlds::NameRules;
+use common::idna::IDN_PREFIX;
+use common::punycode::{decode, encode, MAX_LABEL_LEN};
+use soroban_sdk::Bytes;
+
+// Internationalized names are saved in their punycode form (`xn--` + the encoded label), for example `café` is
+// registered as `xn--caf-dma`. Clients convert the unicode names with `common::idna::to_ascii` (it lowercases, composes
+// the combining marks and encodes the label) before calling the contract, the contract only accepts the canonical form
+// of each name so a visual name can't map to several nodes. The reverse registrar converts the names it receives with
+// the same helper.
+
+#[derive(Clone, Copy, PartialEq)]
+enum Script {
+    // Digits and hyphens can be used with any script
+    Common,
+    Latin,
+    Greek,
+    Cyrillic,
+    Hebrew,
+    Arabic,
+    Thai,
+    // Han, Hiragana and Katakana are used together in Japanese
+    Cjk,
+    Hangul,
+}
+
+// Only lowercase letters of the supported scripts are allowed. Combining marks are rejected so accented letters need
+// to use their precomposed form. Greek and Cyrillic are limited to their basic lowercase letters, the supplement and
+// extended blocks are left out on purpose since they have most of the letters that look like Latin letters (like `ԁ`,
+// `һ` or `ӏ`).
+fn script(code_point: u32) -> Option<Script> {
+    match code_point {
+        0x30..=0x39 | 0x2D => Some(Script::Common),
+        0x61..=0x7A | 0xDF..=0xF6 | 0xF8..=0xFF => Some(Script::Latin),
+        // Lowercase letters of Latin Extended-A
+        0x0101..=0x0137 | 0x014B..=0x0177 if code_point % 2 == 1 => Some(Script::Latin),
+        0x013A..=0x0148 | 0x017A..=0x017E if code_point % 2 == 0 => Some(Script::Latin),
+        0x03AC..=0x03CE => Some(Script::Greek),
+        0x0430..=0x045F => Some(Script::Cyrillic),
+        0x05D0..=0x05EA => Some(Script::Hebrew),
+        0x0620..=0x064A => Some(Script::Arabic),
+        0x0E01..=0x0E3A | 0x0E40..=0x0E4E => Some(Script::Thai),
+        0x3041..=0x3096 | 0x30A1..=0x30FA | 0x30FC | 0x4E00..=0x9FFF => Some(Script::Cjk),
+        0xAC00..=0xD7A3 => Some(Script::Hangul),
+        _ => None,
+    }
+}
+
+// Greek and Cyrillic letters that look like Latin letters (or like the accented Latin letters we accept), a name made
+// only of these letters would look like a Latin name (for example `аре` in Cyrillic looks like `ape`).
+// The list is intentionally partial: it's not the Unicode confusables data, it only has the letters allowed by `script`
+// that look the same as a Latin letter in common fonts. Names that mix these letters with other letters of their script
+// are accepted since they don't look like a Latin name.
+fn is_latin_confusable(code_point: u32) -> bool {
+    matches!(
+        code_point,
+        // а е о р с у х ѕ і ј ё ї
+        0x0430
+            | 0x0435
+            | 0x043E
+            | 0x0440
+            | 0x0441
+            | 0x0443
+            | 0x0445
+            | 0x0455
+            | 0x0456
+            | 0x0458
+            | 0x0451
+            | 0x0457
+            // α γ ι κ ν ο ρ τ υ χ ά ί ϊ ϋ ό ύ
+            | 0x03B1
+            | 0x03B3
+            | 0x03B9
+            | 0x03BA
+            | 0x03BD
+            | 0x03BF
+            | 0x03C1
+            | 0x03C4
+            | 0x03C5
+            | 0x03C7
+            | 0x03AC
+            | 0x03AF
+            | 0x03CA
+            | 0x03CB
+            | 0x03CC
+            | 0x03CD
+    )
+}
+
+pub fn is_idn(domain: &Bytes) -> bool {
+    domain.len() > IDN_PREFIX.len() as u32
+        && domain.slice(0..IDN_PREFIX.len() as u32) == Bytes::from_slice(domain.env(), IDN_PREFIX)
+}
+
+// Decodes the punycode label of an internationalized name, it returns the buffer with the code points and its length
+fn decode_idn(domain: &Bytes) -> Option<([u32; MAX_LABEL_LEN], usize)> {
+    if domain.len() as usize > MAX_LABEL_LEN {
+        return None;
+    }
+
+    let mut label: [u8; MAX_LABEL_LEN] = [0; MAX_LABEL_LEN];
+    let label_len: usize = domain.len() as usize - IDN_PREFIX.len();
+    domain
+        .slice(IDN_PREFIX.len() as u32..)
+        .copy_into_slice(&mut label[..label_len]);
+
+    let mut code_points: [u32; MAX_LABEL_LEN] = [0; MAX_LABEL_LEN];
+    let len: usize = decode(&label[..label_len], &mut code_points).ok()?;
+
+    // Only the canonical encoding is accepted
+    let mut encoded: [u8; MAX_LABEL_LEN] = [0; MAX_LABEL_LEN];
+    let encoded_len: usize = encode(&code_points[..len], &mut encoded).ok()?;
+    if encoded[..encoded_len] != label[..label_len] {
+        return None;
+    }
+
+    Some((code_points, len))
+}
+
+// The length of a name is the amount of characters the users see, so internationalized names are measured using their
+// decoded form
+pub fn name_length(domain: &Bytes) -> u32 {
+    if is_idn(domain) {
+        if let Some((_, len)) = decode_idn(domain) {
+            return len as u32;
+        }
+    }
+
+    domain.len()
+}
+
+// Internationalized names follow the same rules as the ascii names plus:
+// - The punycode label needs to be valid, canonical and it needs to have at least one non ascii character
+// - All the letters need to be from the same script (digits and hyphens can be used with any script)
+// - Greek and Cyrillic names can't be made only of letters that look like Latin letters
+pub fn check_idn(domain: &Bytes, rules: &NameRules) -> Result<(), ContractErrors> {
+    let (code_points, len) = decode_idn(domain).ok_or(ContractErrors::InvalidPunycode)?;
+
+    if code_points[..len]
+        .iter()
+        .all(|code_point| *code_point < 0x80)
+    {
+        return Err(ContractErrors::InvalidPunycode);
+    }
+
+    if (len as u32) < rules.min_length {
+        return Err(ContractErrors::NameTooShort);
+    }
+
+    if len as u32 > rules.max_length {
+        return Err(ContractErrors::NameTooLong);
+    }
+
+    let mut name_script: Script = Script::Common;
+    let mut only_confusables: bool = true;
+    let mut previous: u32 = '-' as u32;
+    for code_point in code_points[..len].iter().copied() {
+        let code_point_script: Script = match script(code_point) {
+            Some(Script::Common) if code_point == '-' as u32 => {
+                if !rules.allow_hyphens {
+                    return Err(ContractErrors::InvalidCharacter);
+                }
+                if previous == '-' as u32 {
+                    return Err(ContractErrors::InvalidHyphen);
+                }
+                Script::Common
+            }
+            Some(Script::Common) if !rules.allow_digits => {
+                return Err(ContractErrors::InvalidCharacter);
+            }
+            Some(script) => script,
+            None => return Err(ContractErrors::InvalidCharacter),
+        };
+
+        if code_point_script != Script::Common {
+            if name_script != Script::Common && name_script != code_point_script {
+                return Err(ContractErrors::MixedScripts);
+            }
+            name_script = code_point_script;
+            only_confusables = only_confusables && is_latin_confusable(code_point);
+        }
+
+        previous = code_point;
+    }
+
+    if previous == '-' as u32 {
+        return Err(ContractErrors::InvalidHyphen);
+    }
+
+    if (name_script == Script::Greek || name_script == Script::Cyrillic) && only_confusables {
+        return Err(ContractErrors::ConfusableName);
+    }
+
+    Ok(())
+}
@@ -1,2 +1,6 @@
+pub mod idn;
+pub mod nft;
 pub mod offers;
+pub mod quotes;
 pub mod records;
+pub mod tlds;
@@ -0,0 +1,71 @@
+use crate::errors::ContractErrors;
+use crate::storage::core::NftConfig;
+use crate::storage::record::{Domain, Record, RecordEntity, RecordKeys, RecordName};
+use soroban_sdk::{contracttype, panic_with_error, Address, Bytes, BytesN, Env, String};
+
+// The base URI is copied into a fixed buffer next to the node in hex, so it has a max length
+pub const MAX_BASE_URI_LENGTH: u32 = 128;
+
+const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenMetadata {
+    pub node: BytesN<32>,
+
+    // The human-readable name (for example `stellar.xlm`), domains registered before we started saving the names
+    // don't have it
+    pub name: Option<Bytes>,
+
+    pub owner: Address,
+    pub address: Address,
+    pub exp_date: u64,
+    pub collateral: u128,
+    pub uri: Option<String>,
+}
+
+// Only domains are tokens, subdomains can't be used as a token id
+pub fn token_domain(e: &Env, node: &BytesN<32>) -> Domain {
+    match e.record(&RecordKeys::Record(node.clone())) {
+        Some(Record::Domain(domain)) => domain,
+        Some(Record::SubDomain(_)) => panic_with_error!(&e, &ContractErrors::InvalidDomain),
+        None => panic_with_error!(&e, &ContractErrors::RecordDoesntExist),
+    }
+}
+
+// The URI of a domain is the base URI followed by the node in hex, it's `None` if there is no base URI
+pub fn token_uri(e: &Env, config: &NftConfig, node: &BytesN<32>) -> Option<String> {
+    let length: usize = config.base_uri.len() as usize;
+    if length == 0 {
+        return None;
+    }
+
+    let mut buffer: [u8; MAX_BASE_URI_LENGTH as usize + 64] =
+        [0; MAX_BASE_URI_LENGTH as usize + 64];
+    config.base_uri.copy_into_slice(&mut buffer[..length]);
+    for (i, byte) in node.to_array().iter().enumerate() {
+        buffer[length + i * 2] = HEX_CHARS[(byte >> 4) as usize];
+        buffer[length + i * 2 + 1] = HEX_CHARS[(byte & 0x0f) as usize];
+    }
+
+    Some(String::from_bytes(&e, &buffer[..length + 64]))
+}
+
+pub fn token_metadata(e: &Env, config: &NftConfig, domain: &Domain) -> TokenMetadata {
+    let name: Option<Bytes> = e.record_name(&domain.node).map(|name: RecordName| {
+        let mut full_name: Bytes = name.domain;
+        full_name.push_back(b'.');
+        full_name.append(&name.tld);
+        full_name
+    });
+
+    TokenMetadata {
+        node: domain.node.clone(),
+        name,
+        owner: domain.owner.clone(),
+        address: domain.address.clone(),
+        exp_date: domain.exp_date,
+        collateral: domain.collateral,
+        uri: token_uri(&e, &config, &domain.node),
+    }
+}
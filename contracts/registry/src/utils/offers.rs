@@ -1,7 +1,11 @@
 use crate::errors::ContractErrors;
-use crate::storage::core::CoreData;
+use crate::events::{
+    emit_buy_offer_burned, emit_buy_offer_set, emit_sale_offer_burned, emit_sale_offer_set,
+};
+use crate::storage::core::{CoreData, OffersConfig};
 use crate::storage::offers::{BuyOffer, Offer, OffersDataKeys, OffersFunc, SaleOffer};
 use crate::storage::record::Domain;
+use num_integer::div_ceil;
 use soroban_sdk::{panic_with_error, token, Address, Env};
 
 // Sets a domain SaleOffer
@@ -17,6 +21,8 @@ pub fn set_sale_offer(e: &Env, domain: &Domain, amount: &u128) {
     e._offers().set(&Offer::SaleOffer(new_sale_offer));
     e._offers()
         .bump(&OffersDataKeys::SaleOffer(domain.node.clone()));
+
+    emit_sale_offer_set(&e, &domain.node, &domain.owner, &amount, &domain.snapshot);
 }
 
 // Sets a new BuyOffer
@@ -45,6 +51,8 @@ pub fn set_new_buy_offer(
     e._offers().set(&Offer::BuyOffer(new_offer));
     e._offers()
         .bump(&OffersDataKeys::BuyOffer(domain.node.clone()));
+
+    emit_buy_offer_set(&e, &domain.node, &caller, &amount, &domain.snapshot);
 }
 
 // Updates an already existing BuyOffer
@@ -96,7 +104,42 @@ pub fn update_buy_offer(
         );
     }
 
+    emit_buy_offer_set(
+        &e,
+        &domain.node,
+        &updated_offer.buyer,
+        &updated_offer.amount,
+        &updated_offer.snapshot,
+    );
+
     e._offers().set(&Offer::BuyOffer(updated_offer));
     e._offers()
         .bump(&OffersDataKeys::BuyOffer(domain.node.clone()));
 }
+
+// The protocol fee of an offer is taken from the profit of the seller (the amount minus the collateral of the domain)
+pub fn offer_fee(offers_config: &OffersConfig, profit: u128) -> u128 {
+    div_ceil(profit * offers_config.fee, 100_0000000)
+}
+
+// Cancels the offers of a domain that is leaving the registry (for example when it's burned), the amount of the buy
+// offer goes back to the buyer
+pub fn burn_domain_offers(e: &Env, core_data: &CoreData, domain: &Domain) {
+    let buy_offer_key: OffersDataKeys = OffersDataKeys::BuyOffer(domain.node.clone());
+    if let Some(Offer::BuyOffer(buy_offer)) = e._offers().get(&buy_offer_key) {
+        token::Client::new(&e, &core_data.col_asset).transfer(
+            &e.current_contract_address(),
+            &buy_offer.buyer,
+            &(buy_offer.amount as i128),
+        );
+
+        e._offers().burn(&buy_offer_key);
+        emit_buy_offer_burned(&e, &domain.node, &buy_offer.buyer, &buy_offer.amount);
+    }
+
+    let sale_offer_key: OffersDataKeys = OffersDataKeys::SaleOffer(domain.node.clone());
+    if e._offers().get(&sale_offer_key).is_some() {
+        e._offers().burn(&sale_offer_key);
+        emit_sale_offer_burned(&e, &domain.node, &domain.owner);
+    }
+}
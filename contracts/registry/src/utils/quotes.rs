@@ -0,0 +1,256 @@
+use crate::errors::ContractErrors;
+use crate::storage::core::{CoreData, CoreDataEntity, OffersConfig};
+use crate::storage::offers::{Offer, OffersDataKeys, OffersFunc};
+use crate::storage::record::{Domain, RecordEntity, RecordName};
+use crate::storage::tlds::{TldConfig, TldStatus, TldsEntity};
+use crate::utils::offers::offer_fee;
+use crate::utils::records::{
+    below_min_ratio, collateral_for_price, collateral_price, duration_fee, expiration_date,
+    generate_node, missing_collateral, usd_price, validate_domain, validate_duration,
+};
+use crate::utils::tlds::domain_tld_status;
+use soroban_sdk::{contracttype, panic_with_error, Bytes, BytesN, Env, Vec};
+
+// All the amounts use 7 decimals, prices are in USD and the rest of the amounts are in the collateral asset
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegistrationQuote {
+    // The USD price of the domain and the price of the collateral asset used to calculate the collateral
+    pub usd_value: u128,
+    pub oracle_price: u128,
+
+    // Locked in the domain, it's returned when the domain is burned
+    pub collateral: u128,
+
+    // The annual fee prorated by the duration, it's not returned
+    pub duration_fee: u128,
+
+    // The amount the owner pays when registering the domain (collateral + duration fee)
+    pub total: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenewalQuote {
+    // Domains registered before we started saving the names can't be priced again, they keep their collateral and both
+    // the USD value and the oracle price are zero
+    pub usd_value: u128,
+    pub oracle_price: u128,
+
+    // The new collateral of the domain: if it's higher than the current one the caller pays the difference
+    // (`collateral_payment`), if it's lower the surplus goes back to the owner (`collateral_refund`)
+    pub collateral: u128,
+    pub collateral_payment: u128,
+    pub collateral_refund: u128,
+
+    pub duration_fee: u128,
+
+    // The amount the caller pays when renewing the domain (collateral payment + duration fee)
+    pub total: u128,
+
+    pub exp_date: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RebalanceQuote {
+    pub usd_value: u128,
+    pub oracle_price: u128,
+
+    // The collateral the domain needs at the current oracle price: if it's higher than the current one the caller pays
+    // the difference (`collateral_payment`), if it's lower the surplus goes back to the owner (`collateral_refund`)
+    pub collateral: u128,
+    pub collateral_payment: u128,
+    pub collateral_refund: u128,
+
+    // If true, the domain can't be transferred until it's rebalanced (sales top it up with the amount of the offer)
+    pub undercollateralized: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct OfferQuote {
+    pub offer: OffersDataKeys,
+    pub amount: u128,
+
+    // The collateral after the sale, undercollateralized domains are topped up with the amount of the offer
+    pub collateral: u128,
+
+    // The protocol fee is taken from the profit (amount - collateral), the seller receives the rest of the amount
+    pub fee: u128,
+    pub seller_amount: u128,
+
+    // Offers made before the domain changed (for example after a transfer) are outdated and can't be taken
+    pub outdated: bool,
+}
+
+// Checks that a domain can be registered (TLD status, name rules, blocked names and duration) and prices it. Both
+// `register_domain` and `registration_quote` use it so the quote fails with the same errors as the registration, it
+// also returns the config of the TLD so the registration doesn't need to load it again.
+pub fn validate_registration(
+    e: &Env,
+    domain: &Bytes,
+    tld: &Bytes,
+    duration: u64,
+) -> (TldConfig, RegistrationQuote) {
+    let tld_config: TldConfig = e
+        .tld_config(&tld)
+        .unwrap_or_else(|| panic_with_error!(&e, &ContractErrors::UnsupportedTLD));
+
+    if e.tld_status(&tld) != TldStatus::Active {
+        panic_with_error!(&e, &ContractErrors::TldClosed);
+    }
+
+    validate_domain(&e, &domain, &tld_config.name_rules);
+
+    let node: BytesN<32> = generate_node(&e, &domain, &tld);
+    if e.is_blocked(&node) {
+        panic_with_error!(&e, &ContractErrors::BlockedName);
+    }
+
+    validate_duration(
+        &e,
+        tld_config.min_duration,
+        duration,
+        expiration_date(&e, e.ledger().timestamp(), duration),
+    );
+
+    let usd_value: u128 = usd_price(&e, &domain, &tld);
+    let oracle_price: u128 =
+        collateral_price(&e, &e.oracle()).unwrap_or_else(|error| panic_with_error!(&e, &error));
+    let collateral: u128 = collateral_for_price(usd_value, oracle_price);
+    let duration_fee: u128 = duration_fee(&e, collateral, duration);
+
+    let quote: RegistrationQuote = RegistrationQuote {
+        usd_value,
+        oracle_price,
+        collateral,
+        duration_fee,
+        total: collateral + duration_fee,
+    };
+
+    (tld_config, quote)
+}
+
+pub fn registration_quote(
+    e: &Env,
+    domain: &Bytes,
+    tld: &Bytes,
+    duration: u64,
+) -> RegistrationQuote {
+    let (_, quote) = validate_registration(&e, &domain, &tld, duration);
+    quote
+}
+
+// It follows the rules of `renew_record`, the caller needs to check first if the domain can be renewed (expired domains
+// can only be renewed during the grace period)
+pub fn renewal_quote(
+    e: &Env,
+    core_data: &CoreData,
+    domain: &Domain,
+    duration: u64,
+) -> RenewalQuote {
+    // If the domain is already expired, the new period starts from now
+    let exp_date: u64 = expiration_date(&e, domain.exp_date.max(e.ledger().timestamp()), duration);
+
+    // Domains of retired TLDs and domains registered before we started saving the names use the min duration of the
+    // core data
+    let name: Option<RecordName> = e.record_name(&domain.node);
+    let min_duration: u64 = name
+        .clone()
+        .and_then(|name| e.tld_config(&name.tld))
+        .map(|config| config.min_duration)
+        .unwrap_or(core_data.min_duration);
+    validate_duration(&e, min_duration, duration, exp_date);
+
+    // Domains of a sunset TLD can't be renewed beyond the sunset date
+    if let TldStatus::Sunset(date) = domain_tld_status(&e, &domain.node) {
+        if exp_date > date {
+            panic_with_error!(&e, &ContractErrors::InvalidDuration);
+        }
+    }
+
+    let (usd_value, oracle_price, collateral) = match name {
+        Some(name) => {
+            let usd_value: u128 = usd_price(&e, &name.domain, &name.tld);
+            let oracle_price: u128 = collateral_price(&e, &e.oracle())
+                .unwrap_or_else(|error| panic_with_error!(&e, &error));
+            (
+                usd_value,
+                oracle_price,
+                collateral_for_price(usd_value, oracle_price),
+            )
+        }
+        None => (0, 0, domain.collateral),
+    };
+
+    let collateral_payment: u128 = collateral.saturating_sub(domain.collateral);
+    let duration_fee: u128 = duration_fee(&e, collateral, duration);
+
+    RenewalQuote {
+        usd_value,
+        oracle_price,
+        collateral,
+        collateral_payment,
+        collateral_refund: domain.collateral.saturating_sub(collateral),
+        duration_fee,
+        total: collateral_payment + duration_fee,
+        exp_date,
+    }
+}
+
+// Domains registered before we started saving the names can't be priced again, so they can't be rebalanced
+pub fn rebalance_quote(e: &Env, domain: &Domain) -> RebalanceQuote {
+    let name: RecordName = e
+        .record_name(&domain.node)
+        .unwrap_or_else(|| panic_with_error!(&e, &ContractErrors::UnpricedDomain));
+
+    let usd_value: u128 = usd_price(&e, &name.domain, &name.tld);
+    let oracle_price: u128 =
+        collateral_price(&e, &e.oracle()).unwrap_or_else(|error| panic_with_error!(&e, &error));
+    let collateral: u128 = collateral_for_price(usd_value, oracle_price);
+
+    RebalanceQuote {
+        usd_value,
+        oracle_price,
+        collateral,
+        collateral_payment: collateral.saturating_sub(domain.collateral),
+        collateral_refund: domain.collateral.saturating_sub(collateral),
+        undercollateralized: below_min_ratio(&e, domain.collateral, collateral),
+    }
+}
+
+// Quotes the sale and the buy offers of a domain (if they exist)
+pub fn offer_quotes(e: &Env, domain: &Domain) -> Vec<OfferQuote> {
+    let mut quotes: Vec<OfferQuote> = Vec::new(&e);
+    let offers_config: OffersConfig = match e.offers_config() {
+        Some(offers_config) => offers_config,
+        None => return quotes,
+    };
+
+    let top_up: u128 = missing_collateral(&e, &domain).unwrap_or(0);
+    let collateral: u128 = domain.collateral + top_up;
+    for key in [
+        OffersDataKeys::SaleOffer(domain.node.clone()),
+        OffersDataKeys::BuyOffer(domain.node.clone()),
+    ] {
+        let (amount, snapshot) = match e._offers().get(&key) {
+            Some(Offer::SaleOffer(offer)) => (offer.amount, offer.snapshot),
+            Some(Offer::BuyOffer(offer)) => (offer.amount, offer.snapshot),
+            None => continue,
+        };
+
+        let fee: u128 = offer_fee(&offers_config, amount.saturating_sub(collateral));
+        quotes.push_back(OfferQuote {
+            offer: key,
+            amount,
+            collateral,
+            fee,
+            seller_amount: amount.saturating_sub(top_up + fee),
+            outdated: snapshot != domain.snapshot,
+        });
+    }
+
+    quotes
+}
@@ -1,24 +1,72 @@
 use crate::errors::ContractErrors;
-use soroban_sdk::{panic_with_error, symbol_short, Address, Bytes, BytesN, Env};
+use crate::events::{
+    emit_duration_fee_paid, emit_record_liquidated, emit_record_released, emit_record_set,
+    emit_record_transferred, emit_sub_record_burned,
+};
+use crate::storage::approvals::{Approval, ApprovalsEntity};
+use crate::storage::core::{
+    CoreData, CoreDataEntity, DurationConfig, ExpirationConfig, OracleConfig,
+};
+use crate::storage::owners::OwnersEntity;
+use crate::storage::pricing::PricingEntity;
+use crate::storage::record::{Domain, Record, RecordEntity, RecordKeys, RecordName, SubDomain};
+use crate::storage::subdomains::SubdomainsEntity;
+use crate::storage::tlds::{NameRules, TldsEntity};
+use crate::utils::idn::{check_idn, is_idn, name_length};
+use crate::utils::offers::burn_domain_offers;
+use crate::utils::quotes::validate_registration;
+use crate::utils::tlds::transfer_protocol_fee;
+use num_integer::div_ceil;
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{panic_with_error, symbol_short, token, Address, Bytes, BytesN, Env, Vec};
 
 mod oracle {
     soroban_sdk::contractimport!(file = "../../oracle.wasm");
 }
 
-// Currently there are forbidden types of domains:
-// - Domains with numbers
-// - Domains with special characters
-// - Domains with uppercase letters
-// - Domains that are longer than 15 characters
-pub fn validate_domain(e: &Env, domain: &Bytes) {
-    if domain.len() > 15 {
-        panic_with_error!(&e, &ContractErrors::InvalidDomain);
+// Domains need to follow the name rules of their TLD (the default rules only allow 1 to 15 lowercase letters):
+// - The length needs to be between the min and max length
+// - Lowercase letters are always allowed, digits and hyphens only if the rules allow them
+// - Hyphens can't be at the start or the end of the name and there can't be two hyphens in a row
+// - If the rules allow them, internationalized names use their punycode form (see `check_idn`)
+pub fn check_domain(domain: &Bytes, rules: &NameRules) -> Result<(), ContractErrors> {
+    if rules.allow_idn && is_idn(domain) {
+        return check_idn(domain, rules);
     }
 
+    if domain.len() < rules.min_length {
+        return Err(ContractErrors::NameTooShort);
+    }
+
+    if domain.len() > rules.max_length {
+        return Err(ContractErrors::NameTooLong);
+    }
+
+    let mut previous: u8 = b'-';
     for byte in domain.iter() {
-        if byte < 97 || byte > 122 {
-            panic_with_error!(&e, &ContractErrors::InvalidDomain);
+        match byte {
+            b'a'..=b'z' => {}
+            b'0'..=b'9' if rules.allow_digits => {}
+            b'-' if rules.allow_hyphens => {
+                if previous == b'-' {
+                    return Err(ContractErrors::InvalidHyphen);
+                }
+            }
+            _ => return Err(ContractErrors::InvalidCharacter),
         }
+        previous = byte;
+    }
+
+    if previous == b'-' {
+        return Err(ContractErrors::InvalidHyphen);
+    }
+
+    Ok(())
+}
+
+pub fn validate_domain(e: &Env, domain: &Bytes, rules: &NameRules) {
+    if let Err(error) = check_domain(domain, rules) {
+        panic_with_error!(&e, &error);
     }
 }
 
@@ -36,44 +84,622 @@ pub fn generate_node(e: &Env, domain: &Bytes, parent: &Bytes) -> BytesN<32> {
     e.crypto().keccak256(&node_builder).to_bytes()
 }
 
-// This calculates how much will it costs to set a new domain based on its length
+// This calculates how much will it costs to set a new domain
 // It first defines the price in USD and, then it calculates the amount of collateral to request
-pub fn record_price(e: &Env, oracle_addr: &Address, length: u32) -> (u128, u128) {
-    let usd_value: u128 = if length >= 5 {
-        20_0000000
-    } else if length == 4 {
-        35_0000000
-    } else if length == 3 {
-        61_2500000
-    } else if length == 2 {
-        107_1800000
-    } else {
-        187_5700000
-    };
+pub fn record_price(
+    e: &Env,
+    oracle_addr: &Address,
+    domain: &Bytes,
+    tld: &Bytes,
+) -> Result<(u128, u128), ContractErrors> {
+    let usd_value: u128 = usd_price(&e, &domain, &tld);
+    Ok((usd_value, collateral_amount(&e, &oracle_addr, usd_value)?))
+}
+
+// Premium domains have their own price, the rest of the domains are priced based on their length using the pricing
+// table of the TLD (or the default one if the TLD doesn't have its own table)
+pub fn usd_price(e: &Env, domain: &Bytes, tld: &Bytes) -> u128 {
+    if let Some(price) = e.premium_price(&generate_node(&e, &domain, &tld)) {
+        return price;
+    }
+
+    let length_prices: Vec<u128> = e.pricing_table(&Some(tld.clone())).length_prices;
+    let index: u32 = name_length(domain).min(length_prices.len()).max(1) - 1;
+
+    length_prices.get(index).unwrap()
+}
+
+// Converts a USD value (with 7 decimals) into the amount of collateral using the oracle price
+pub fn collateral_amount(
+    e: &Env,
+    oracle_addr: &Address,
+    usd_value: u128,
+) -> Result<u128, ContractErrors> {
+    Ok(collateral_for_price(
+        usd_value,
+        collateral_price(&e, &oracle_addr)?,
+    ))
+}
+
+pub fn collateral_for_price(usd_value: u128, price: u128) -> u128 {
+    (usd_value * 10u128.pow(7)) / price
+}
+
+// Returns the price of the collateral (with 7 decimals) following the oracle config.
+// If the main oracle doesn't have a valid price we try with the fallback oracle, if the fallback doesn't have one either
+// we return the error of the main oracle.
+pub fn collateral_price(e: &Env, oracle_addr: &Address) -> Result<u128, ContractErrors> {
+    let config: OracleConfig = e.oracle_config();
+
+    match oracle_price(&e, &oracle_addr, &config) {
+        Ok(price) => Ok(price),
+        Err(error) => match &config.fallback {
+            Some(fallback) => oracle_price(&e, &fallback, &config).map_err(|_| error),
+            None => Err(error),
+        },
+    }
+}
 
+// We use the `try_` methods of the oracle because a broken oracle shouldn't make the whole transaction fail before we
+// check the fallback oracle
+fn oracle_price(
+    e: &Env,
+    oracle_addr: &Address,
+    config: &OracleConfig,
+) -> Result<u128, ContractErrors> {
     let oracle_client: oracle::Client = oracle::Client::new(&e, oracle_addr);
-    let decimals: u32 = oracle_client.decimals();
-    let rate_price: u128 = oracle_client
-        .lastprice(&oracle::Asset::Other(symbol_short!("XLM")))
-        .unwrap()
-        .price as u128;
+    let asset: oracle::Asset = oracle::Asset::Other(symbol_short!("XLM"));
+
+    let decimals: u32 = match oracle_client.try_decimals() {
+        Ok(Ok(decimals)) => decimals,
+        _ => return Err(ContractErrors::OracleUnavailable),
+    };
+
+    let last: oracle::PriceData = match oracle_client.try_lastprice(&asset) {
+        Ok(Ok(Some(last))) => last,
+        _ => return Err(ContractErrors::OracleUnavailable),
+    };
+
+    if config.max_age > 0 && last.timestamp + config.max_age < e.ledger().timestamp() {
+        return Err(ContractErrors::StalePrice);
+    }
 
-    let collateral_price: u128 = if decimals > 7 {
+    // Negative or zero prices are rejected before the average, so a bad record can't wrap around when it's cast
+    if last.price <= 0 {
+        return Err(ContractErrors::PriceOutOfBounds);
+    }
+
+    let mut total: i128 = last.price;
+    let records: u32 = config.twap_records.max(1);
+    if records > 1 {
+        let resolution: u64 = match oracle_client.try_resolution() {
+            Ok(Ok(resolution)) => resolution as u64,
+            _ => return Err(ContractErrors::OracleUnavailable),
+        };
+
+        for i in 1..records {
+            let timestamp: u64 = last
+                .timestamp
+                .checked_sub(resolution * i as u64)
+                .ok_or(ContractErrors::OracleUnavailable)?;
+
+            let price: i128 = match oracle_client.try_price(&asset, &timestamp) {
+                Ok(Ok(Some(record))) => record.price,
+                _ => return Err(ContractErrors::OracleUnavailable),
+            };
+
+            if price <= 0 {
+                return Err(ContractErrors::PriceOutOfBounds);
+            }
+
+            total = total
+                .checked_add(price)
+                .ok_or(ContractErrors::PriceOutOfBounds)?;
+        }
+    }
+
+    let rate_price: u128 = (total / records as i128) as u128;
+    let price: u128 = if decimals > 7 {
         rate_price / 10u128.pow(decimals - 7)
     } else {
         rate_price
     };
 
-    let collateral_amount: u128 = (usd_value * 10u128.pow(7)) / collateral_price;
+    if price == 0 || price < config.min_price || (config.max_price > 0 && price > config.max_price)
+    {
+        return Err(ContractErrors::PriceOutOfBounds);
+    }
+
+    Ok(price)
+}
+
+// Walks up the parents of a subdomain until it finds the root domain.
+// It returns the root domain and the depth of the subdomain (1 for `sub.example.xlm`).
+// Subdomains deeper than the max depth are rejected because we don't want to walk an unbounded amount of parents.
+pub fn sub_root(e: &Env, sub: &SubDomain) -> Result<(Domain, u32), ContractErrors> {
+    let max_depth: u32 = e.subdomains_config().max_depth;
+    let mut parent: BytesN<32> = sub.parent.clone();
+    let mut depth: u32 = 1;
+
+    while depth <= max_depth {
+        if let Some(Record::Domain(domain)) = e.record(&RecordKeys::Record(parent.clone())) {
+            return Ok((domain, depth));
+        }
+
+        match e.record(&RecordKeys::SubRecord(parent.clone())) {
+            Some(Record::SubDomain(parent_sub)) => {
+                parent = parent_sub.parent;
+                depth += 1;
+            }
+            _ => return Err(ContractErrors::InvalidParent),
+        }
+    }
+
+    Err(ContractErrors::MaxDepthExceeded)
+}
+
+// A subdomain is valid if its root domain is not expired and the snapshot is the same as the root domain
+// Parents between the subdomain and the root have the same snapshot, so we only need to check the root
+pub fn validate_sub(e: &Env, sub: &SubDomain) -> Result<(Domain, u32), ContractErrors> {
+    let (root, depth) = sub_root(&e, &sub)?;
+
+    if root.exp_date < e.ledger().timestamp() {
+        return Err(ContractErrors::ExpiredDomain);
+    }
+
+    if root.snapshot != sub.snapshot {
+        return Err(ContractErrors::OutdatedSub);
+    }
+
+    Ok((root, depth))
+}
+
+// The controller of a record is the address that can manage it: the owner of a domain or the owner of a subdomain.
+// Subdomains without an owner are managed by the controller of their parent, so we go up until we find an owner (at the
+// end of the chain is always the owner of the root domain).
+pub fn sub_controller(e: &Env, sub: &SubDomain) -> Result<Address, ContractErrors> {
+    match e.sub_owner(&sub.node) {
+        Some(owner) => Ok(owner),
+        None => parent_controller(e, sub),
+    }
+}
+
+// Same as `sub_controller` but it skips the owner of the subdomain, this is the address who can create or revoke it
+pub fn parent_controller(e: &Env, sub: &SubDomain) -> Result<Address, ContractErrors> {
+    let max_depth: u32 = e.subdomains_config().max_depth;
+    let mut parent: BytesN<32> = sub.parent.clone();
+    let mut depth: u32 = 1;
+
+    while depth <= max_depth {
+        if let Some(Record::Domain(domain)) = e.record(&RecordKeys::Record(parent.clone())) {
+            return Ok(domain.owner);
+        }
+
+        match e.record(&RecordKeys::SubRecord(parent.clone())) {
+            Some(Record::SubDomain(parent_sub)) => {
+                if let Some(owner) = e.sub_owner(&parent_sub.node) {
+                    return Ok(owner);
+                }
+
+                parent = parent_sub.parent;
+                depth += 1;
+            }
+            _ => return Err(ContractErrors::InvalidParent),
+        }
+    }
+
+    Err(ContractErrors::MaxDepthExceeded)
+}
+
+// Resolves a full name like `a.b.example.xlm`: it splits the labels, validates them and walks the hierarchy from the
+// root domain to the last subdomain. Labels follow the name rules of the TLD (or the default rules if the TLD doesn't
+// have a config anymore).
+// It returns an error instead of panicking so it can be used by other contracts without failing their calls.
+pub fn resolve_name(e: &Env, name: &Bytes) -> Result<Record, ContractErrors> {
+    let mut labels: Vec<Bytes> = Vec::new(&e);
+    let mut start: u32 = 0;
+    for (index, byte) in name.iter().enumerate() {
+        if byte == b'.' {
+            labels.push_back(name.slice(start..index as u32));
+            start = index as u32 + 1;
+        }
+    }
+    labels.push_back(name.slice(start..));
+
+    if labels.len() < 2 {
+        return Err(ContractErrors::InvalidDomain);
+    }
+
+    if labels.len() - 2 > e.subdomains_config().max_depth {
+        return Err(ContractErrors::MaxDepthExceeded);
+    }
+
+    let tld: Bytes = labels.pop_back_unchecked();
+    let domain: Bytes = labels.pop_back_unchecked();
+    check_domain(&tld, &NameRules::default())?;
+
+    let rules: NameRules = e
+        .tld_config(&tld)
+        .map(|config| config.name_rules)
+        .unwrap_or_default();
+    check_domain(&domain, &rules)?;
+
+    let root: Domain = match e.record(&RecordKeys::Record(generate_node(&e, &domain, &tld))) {
+        Some(Record::Domain(root)) => root,
+        _ => return Err(ContractErrors::RecordDoesntExist),
+    };
+
+    if root.exp_date < e.ledger().timestamp() {
+        return Err(ContractErrors::ExpiredDomain);
+    }
+
+    let mut node: BytesN<32> = root.node.clone();
+    let mut record: Record = Record::Domain(root.clone());
+    while let Some(label) = labels.pop_back() {
+        check_domain(&label, &rules)?;
+        node = generate_node(&e, &label, &Bytes::from(node));
+
+        record = match e.record(&RecordKeys::SubRecord(node.clone())) {
+            Some(Record::SubDomain(sub)) if sub.snapshot == root.snapshot => Record::SubDomain(sub),
+            Some(Record::SubDomain(_)) => return Err(ContractErrors::OutdatedSub),
+            _ => return Err(ContractErrors::RecordDoesntExist),
+        };
+    }
+
+    Ok(record)
+}
+
+// Transfers a domain to a new owner, the new snapshot invalidates the subdomains, the offers and the approval of the
+// domain
+pub fn transfer_domain(e: &Env, mut domain: Domain, to: Address) {
+    require_collateralized(&e, &domain);
+    emit_record_transferred(&e, &domain.node, &domain.owner, &to);
+    e.remove_owned_domain(&domain.owner, &domain.node);
+    e.add_owned_domain(&to, &domain.node);
+    domain.owner = to;
+    domain.snapshot = e.ledger().timestamp();
+    e.burn_approval(&domain.node);
+    e.set_record(&Record::Domain(domain.clone()));
+    e.bump_record(&RecordKeys::Record(domain.node));
+}
+
+// Checks if the collateral of a domain is below the `min_ratio` of the collateral config, `required` is the collateral
+// the domain needs at the current oracle price
+pub fn below_min_ratio(e: &Env, collateral: u128, required: u128) -> bool {
+    let min_ratio: u128 = e.collateral_config().min_ratio;
+    min_ratio > 0 && collateral * 100_0000000 < required * min_ratio
+}
+
+// Returns the collateral an undercollateralized domain is missing to reach the collateral it needs at the current
+// oracle price, `None` if the domain is not below the `min_ratio`. Domains registered before we started saving the
+// names can't be priced again and the check is skipped if the oracle doesn't have a usable price, so in both cases the
+// domain is never undercollateralized.
+pub fn missing_collateral(e: &Env, domain: &Domain) -> Option<u128> {
+    if e.collateral_config().min_ratio == 0 {
+        return None;
+    }
+
+    let name: RecordName = e.record_name(&domain.node)?;
+    let (_, required) = record_price(&e, &e.oracle(), &name.domain, &name.tld).ok()?;
+    if below_min_ratio(&e, domain.collateral, required) {
+        Some(required - domain.collateral)
+    } else {
+        None
+    }
+}
+
+// Undercollateralized domains can't be transferred until they are rebalanced
+pub fn require_collateralized(e: &Env, domain: &Domain) {
+    if missing_collateral(&e, &domain).is_some() {
+        panic_with_error!(&e, &ContractErrors::Undercollateralized);
+    }
+}
+
+// Approvals are only valid until their `live_until_ledger` and while the domain keeps the snapshot it had when they
+// were given.
+pub fn active_approval(e: &Env, domain: &Domain) -> Option<Approval> {
+    e.approval(&domain.node).filter(|approval| {
+        approval.snapshot == domain.snapshot && approval.live_until_ledger >= e.ledger().sequence()
+    })
+}
+
+// The owner of a domain, an address approved for the domain or an operator of the owner can transfer the domain.
+pub fn can_transfer(e: &Env, domain: &Domain, spender: &Address) -> bool {
+    if domain.owner == *spender || e.is_operator(&domain.owner, spender) {
+        return true;
+    }
+
+    match active_approval(&e, &domain) {
+        Some(approval) => approval.spender == *spender,
+        None => false,
+    }
+}
+
+// The max amount of subdomains burned together with a record in a single call. Subdomains that don't fit stay in the
+// index of their burned parent and anyone can remove them later with `burn_orphan_subdomains`.
+pub const MAX_BURNED_SUBDOMAINS: u32 = 20;
+
+// Removes a subdomain from the storage and from the index of its parent, its children are not touched
+pub fn burn_sub(e: &Env, parent: &BytesN<32>, node: &BytesN<32>) {
+    e.burn_record(&RecordKeys::SubRecord(node.clone()));
+    e.burn_sub_owner(node);
+    e.remove_subdomain(parent, node);
+    emit_sub_record_burned(&e, node, parent);
+}
+
+// Burns the subdomains of a parent (and their children) until `budget` subdomains have been burned. Children are burned
+// before their parent, so a subdomain is only burned once it doesn't have children left. The list is walked from the
+// end so removing a subdomain doesn't move the others. Only the subdomains in the index can be found, so subdomains
+// created before we started indexing them are not burned with their parent.
+pub fn burn_subdomains(e: &Env, parent: &BytesN<32>, budget: &mut u32) {
+    while *budget > 0 {
+        let count: u32 = e.subdomains_count(parent);
+        if count == 0 {
+            return;
+        }
+
+        let node: BytesN<32> = e.subdomain(parent, count - 1).unwrap();
+        burn_subdomains(&e, &node, budget);
+        if *budget == 0 {
+            return;
+        }
+
+        burn_sub(&e, parent, &node);
+        *budget -= 1;
+    }
+}
+
+// Checks if the grace period of an expired domain is over, once it's over anyone can register the domain again
+pub fn is_claimable(e: &Env, domain: &Domain) -> bool {
+    domain
+        .exp_date
+        .saturating_add(e.expiration_config().grace_period)
+        < e.ledger().timestamp()
+}
+
+// Sends the `protocol_share` of the collateral of an expired domain to the `fee_taker`, nothing is taken if the offers
+// config hasn't been set
+fn take_protocol_share(e: &Env, core_data: &CoreData, domain: &Domain) -> u128 {
+    let config: ExpirationConfig = e.expiration_config();
+
+    match e.offers_config() {
+        Some(offers_config) if config.protocol_share > 0 => {
+            let amount: u128 = (domain.collateral * config.protocol_share) / 100_0000000;
+            transfer_protocol_fee(
+                &e,
+                &core_data.col_asset,
+                &e.current_contract_address(),
+                &offers_config.fee_taker,
+                &domain.node,
+                amount,
+            );
+            amount
+        }
+        _ => 0,
+    }
+}
+
+// Releases the collateral of an expired domain before it gets registered by someone else.
+// The protocol share is sent to the `fee_taker` and the rest goes back to the old owner of the domain, its offers are
+// canceled so buyers get their escrow back.
+pub fn release_expired_domain(e: &Env, core_data: &CoreData, domain: &Domain) {
+    let protocol_amount: u128 = take_protocol_share(&e, &core_data, &domain);
+
+    let owner_amount: u128 = domain.collateral - protocol_amount;
+    token::Client::new(&e, &core_data.col_asset).transfer(
+        &e.current_contract_address(),
+        &domain.owner,
+        &(owner_amount as i128),
+    );
+
+    e.burn_approval(&domain.node);
+    e.remove_owned_domain(&domain.owner, &domain.node);
+    burn_domain_offers(&e, &core_data, &domain);
+    emit_record_released(
+        &e,
+        &domain.node,
+        &domain.owner,
+        &owner_amount,
+        &protocol_amount,
+    );
+}
+
+// Removes an expired domain whose grace period is over together with its subdomains and offers. The collateral is
+// split between the `fee_taker` (the protocol share), the keeper who liquidates the domain and the old owner (the rest).
+pub fn liquidate_domain(e: &Env, core_data: &CoreData, domain: &Domain, keeper: &Address) {
+    let protocol_amount: u128 = take_protocol_share(&e, &core_data, &domain);
+    let keeper_reward: u128 = ((domain.collateral * e.liquidation_config().keeper_reward)
+        / 100_0000000)
+        .min(domain.collateral - protocol_amount);
+    let owner_amount: u128 = domain.collateral - protocol_amount - keeper_reward;
+
+    if keeper_reward > 0 {
+        token::Client::new(&e, &core_data.col_asset).transfer(
+            &e.current_contract_address(),
+            keeper,
+            &(keeper_reward as i128),
+        );
+    }
+    token::Client::new(&e, &core_data.col_asset).transfer(
+        &e.current_contract_address(),
+        &domain.owner,
+        &(owner_amount as i128),
+    );
+
+    e.burn_record(&RecordKeys::Record(domain.node.clone()));
+    e.burn_record_name(&domain.node);
+    e.burn_approval(&domain.node);
+    e.remove_owned_domain(&domain.owner, &domain.node);
+    let mut budget: u32 = MAX_BURNED_SUBDOMAINS;
+    burn_subdomains(&e, &domain.node, &mut budget);
+    burn_domain_offers(&e, &core_data, &domain);
+
+    emit_record_liquidated(
+        &e,
+        &domain.node,
+        &domain.owner,
+        keeper,
+        &owner_amount,
+        &keeper_reward,
+        &protocol_amount,
+    );
+}
+
+// The duration needs to be at least the min duration of the TLD and the new expiration date can't be further in the
+// future than the max duration of the config
+// Adds the duration to the start of the period, durations that go beyond the max timestamp fail with `InvalidDuration`
+pub fn expiration_date(e: &Env, start: u64, duration: u64) -> u64 {
+    start
+        .checked_add(duration)
+        .unwrap_or_else(|| panic_with_error!(&e, &ContractErrors::InvalidDuration))
+}
+
+pub fn validate_duration(e: &Env, min_duration: u64, duration: u64, exp_date: u64) {
+    let config: DurationConfig = e.duration_config();
+    if duration < min_duration
+        || (config.max_duration > 0 && exp_date - e.ledger().timestamp() > config.max_duration)
+    {
+        panic_with_error!(&e, &ContractErrors::InvalidDuration);
+    }
+}
+
+// The annual fee is a share of the collateral prorated by the duration (in seconds). If there is no annual fee or the
+// offers config hasn't been set, nothing is charged.
+pub fn duration_fee(e: &Env, collateral: u128, duration: u64) -> u128 {
+    let config: DurationConfig = e.duration_config();
+    if config.annual_fee == 0 || e.offers_config().is_none() {
+        return 0;
+    }
+
+    // Without a max duration the caller can choose a duration long enough to overflow the fee
+    let fee: u128 = collateral
+        .checked_mul(config.annual_fee)
+        .and_then(|value| value.checked_mul(duration as u128))
+        .unwrap_or_else(|| panic_with_error!(&e, &ContractErrors::InvalidDuration));
+
+    div_ceil(fee, 100_0000000 * 3600 * 24 * 365)
+}
+
+// The annual fee goes to the `fee_taker` (minus the revenue share of the TLD operator)
+pub fn pay_duration_fee(
+    e: &Env,
+    core_data: &CoreData,
+    payer: &Address,
+    node: &BytesN<32>,
+    collateral: u128,
+    duration: u64,
+) {
+    let config: DurationConfig = e.duration_config();
+    let offers_config = match e.offers_config() {
+        Some(offers_config) if config.annual_fee > 0 => offers_config,
+        _ => return,
+    };
+
+    let amount: u128 = duration_fee(&e, collateral, duration);
+
+    transfer_protocol_fee(
+        &e,
+        &core_data.col_asset,
+        payer,
+        &offers_config.fee_taker,
+        node,
+        amount,
+    );
+
+    emit_duration_fee_paid(&e, node, payer, &amount);
+}
+
+// The commitment of a registration is the hash of the name, the owner and a secret chosen by the user, so the name
+// can't be known (and registered by someone else) until it's revealed
+pub fn commitment_hash(
+    e: &Env,
+    domain: &Bytes,
+    tld: &Bytes,
+    owner: &Address,
+    secret: &BytesN<32>,
+) -> BytesN<32> {
+    e.crypto()
+        .keccak256(&(domain.clone(), tld.clone(), owner.clone(), secret.clone()).to_xdr(&e))
+        .to_bytes()
+}
+
+// Registers a new domain (or an expired domain whose grace period is over), it's used by both `set_record` and
+// `reveal_and_register` so the caller needs to check the auth of the owner first
+pub fn register_domain(
+    e: &Env,
+    domain: Bytes,
+    tld: Bytes,
+    owner: Address,
+    address: Address,
+    duration: u64,
+) {
+    let core_data: CoreData = e.core_data().unwrap();
+    let node_hash: BytesN<32> = generate_node(&e, &domain, &tld);
+    let record_key: RecordKeys = RecordKeys::Record(node_hash.clone());
+
+    // We check if the record already exists, if it does then we panic unless the domain is expired and its grace
+    // period is over. In that case the old collateral is released and the new record gets a new snapshot so old
+    // subdomains and offers are invalidated.
+    if let Some(record) = e.record(&record_key) {
+        match record {
+            Record::Domain(old_domain) if is_claimable(&e, &old_domain) => {
+                release_expired_domain(&e, &core_data, &old_domain);
+            }
+            _ => panic_with_error!(&e, &ContractErrors::RecordAlreadyExist),
+        }
+    }
+
+    // Subdomains left in the index by the previous owner are burned so they don't get mixed with the new ones, if there
+    // are more than `MAX_BURNED_SUBDOMAINS` the rest need to be removed with `burn_orphan_subdomains` first
+    let mut budget: u32 = MAX_BURNED_SUBDOMAINS;
+    burn_subdomains(&e, &node_hash, &mut budget);
+    if e.subdomains_count(&node_hash) > 0 {
+        panic_with_error!(&e, &ContractErrors::PendingSubdomains);
+    }
+
+    let (tld_config, quote) = validate_registration(&e, &domain, &tld, duration);
+
+    // Only the operator can register the domains of a private TLD
+    if !tld_config.public {
+        tld_config.operator.clone().unwrap().require_auth();
+    }
+
+    let exp_date: u64 = expiration_date(&e, e.ledger().timestamp(), duration);
+    let collateral: u128 = quote.collateral;
+
+    // The name is saved first so the annual fee can find the operator of the TLD
+    let name: RecordName = RecordName { domain, tld };
+    e.set_record_name(&node_hash, &name);
+
+    token::Client::new(&e, &core_data.col_asset).transfer(
+        &owner,
+        &e.current_contract_address(),
+        &(collateral as i128),
+    );
+    pay_duration_fee(&e, &core_data, &owner, &node_hash, collateral, duration);
+
+    let new_domain: Domain = Domain {
+        node: node_hash,
+        owner,
+        address,
+        exp_date,
+        collateral,
+        snapshot: e.ledger().timestamp(),
+    };
+
+    e.set_record(&Record::Domain(new_domain.clone()));
+    e.add_owned_domain(&new_domain.owner, &new_domain.node);
+    emit_record_set(&e, &new_domain, &name);
 
-    (usd_value, collateral_amount)
+    e.bump_record(&record_key);
 }
 
 #[cfg(test)]
 mod test_records_utils {
+    use crate::contract::RegistryContract;
     use crate::utils::records::record_price;
     use soroban_sdk::testutils::Ledger;
-    use soroban_sdk::{symbol_short, Address, Env, String};
+    use soroban_sdk::{symbol_short, Address, Bytes, Env, String};
 
     mod oracle {
         soroban_sdk::contractimport!(file = "../../oracle.wasm");
@@ -98,26 +724,31 @@ mod test_records_utils {
             .price
             / 10i128.pow(oracle_client.decimals() - 7);
 
+        // Prices are read from the pricing table, so we need to run it inside the contract
+        let contract_id: Address = e.register_contract(None, RegistryContract);
+        let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+        let names: [&str; 5] = ["a", "ab", "abc", "abcd", "abcde"];
+
         for i in 1..6 {
             e.budget().reset_default();
+            let domain: Bytes = Bytes::from_slice(&e, names[i - 1].as_bytes());
+            let (usd_value, collateral_amount) = e.as_contract(&contract_id, || {
+                record_price(&e, &oracle_addr, &domain, &tld).unwrap()
+            });
+
             if i == 1 {
-                let (usd_value, collateral_amount) = record_price(&e, &oracle_addr, i);
                 assert_eq!(usd_value, 187_5700000);
                 assert_eq!(collateral_amount, 642_3867732);
             } else if i == 2 {
-                let (usd_value, collateral_amount) = record_price(&e, &oracle_addr, i);
                 assert_eq!(usd_value, 107_1800000);
                 assert_eq!(collateral_amount, 367_0683710);
             } else if i == 3 {
-                let (usd_value, collateral_amount) = record_price(&e, &oracle_addr, i);
                 assert_eq!(usd_value, 61_2500000);
                 assert_eq!(collateral_amount, 209_7680325);
             } else if i == 4 {
-                let (usd_value, collateral_amount) = record_price(&e, &oracle_addr, i);
                 assert_eq!(usd_value, 35_0000000);
                 assert_eq!(collateral_amount, 119_8674471);
             } else {
-                let (usd_value, collateral_amount) = record_price(&e, &oracle_addr, i);
                 assert_eq!(usd_value, 20_0000000);
                 assert_eq!(collateral_amount, 68_4956840);
             }
@@ -0,0 +1,65 @@
+use crate::errors::ContractErrors;
+use crate::storage::record::{RecordEntity, RecordName};
+use crate::storage::tlds::{NameRules, TldConfig, TldStatus, TldsEntity};
+use crate::utils::records::validate_domain;
+use soroban_sdk::{panic_with_error, token, Address, Bytes, BytesN, Env};
+
+// TLDs follow the default name rules and private TLDs need an operator who can register their domains
+pub fn validate_tld_config(e: &Env, tld: &Bytes, config: &TldConfig) {
+    validate_domain(&e, &tld, &NameRules::default());
+
+    if (!config.public && config.operator.is_none())
+        || config.min_duration == 0
+        || config.revenue_share > 100_0000000
+        || config.name_rules.min_length == 0
+        || config.name_rules.min_length > config.name_rules.max_length
+        || config.name_rules.max_length > 63
+    {
+        panic_with_error!(&e, &ContractErrors::InvalidConfig);
+    }
+
+    // An empty pricing table means the TLD uses the default table
+    if config.pricing_table.length_prices.contains(0) {
+        panic_with_error!(&e, &ContractErrors::InvalidConfig);
+    }
+}
+
+// Sends a protocol fee paid for a domain to the `fee_taker`, if the TLD of the domain has an operator then the operator
+// takes its revenue share of the fee.
+// Domains registered before we started saving the names don't have a known TLD, so the whole fee goes to the `fee_taker`
+pub fn transfer_protocol_fee(
+    e: &Env,
+    col_asset: &Address,
+    from: &Address,
+    fee_taker: &Address,
+    node: &BytesN<32>,
+    amount: u128,
+) {
+    let col_client = token::Client::new(&e, col_asset);
+
+    let operator_amount: u128 = match e
+        .record_name(node)
+        .and_then(|name: RecordName| e.tld_config(&name.tld))
+    {
+        Some(TldConfig {
+            operator: Some(operator),
+            revenue_share,
+            ..
+        }) if revenue_share > 0 => {
+            let operator_amount: u128 = (amount * revenue_share) / 100_0000000;
+            col_client.transfer(from, &operator, &(operator_amount as i128));
+            operator_amount
+        }
+        _ => 0,
+    };
+
+    col_client.transfer(from, fee_taker, &((amount - operator_amount) as i128));
+}
+
+// Domains registered before we started saving the names don't have a known TLD, we treat them as active
+pub fn domain_tld_status(e: &Env, node: &BytesN<32>) -> TldStatus {
+    match e.record_name(node) {
+        Some(name) => e.tld_status(&name.tld),
+        None => TldStatus::Active,
+    }
+}
@@ -4,8 +4,11 @@ use crate::{
     registry::{ContractErrors as RegistryErrors, Record, RecordKeys},
     types::{CoreDataKeys, Domain},
 };
-use common::utils::generate_node;
-use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, BytesN, Env, IntoVal};
+use common::{admin, idna::label_to_ascii, utils::generate_node};
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, symbol_short, token, Address, Bytes, BytesN, Env,
+    IntoVal, Vec,
+};
 
 const LEDGER_DAY: u32 = 17_280;
 
@@ -15,7 +18,7 @@ pub trait ReverseRegistrarTrait {
     // # Arguments
     //
     // * `e` - The environment in which the contract is executed.
-    // * `admin` - The address of the admin.
+    // * `admin` - The address of the admin, once it's set it can only be changed with `propose_admin`.
     // * `registry` - The address of the registry.
     // * `fee` - The fee to be paid for adding or updating a reverse domain.
     // * `currency` - The address of the currency to be used for the fee.
@@ -37,7 +40,71 @@ pub trait ReverseRegistrarTrait {
     // * `hash` - The hash of the new contract.
     fn upgrade(e: Env, hash: BytesN<32>);
 
+    // Propose a new admin, the new admin needs to accept it with `accept_admin`.
+    //
+    // # Arguments
+    //
+    // * `e` - The environment in which the contract is executed.
+    // * `new_admin` - The address of the proposed admin.
+    fn propose_admin(e: Env, new_admin: Address);
+
+    // Accept the admin role, it needs to be signed by the pending admin.
+    //
+    // # Arguments
+    //
+    // * `e` - The environment in which the contract is executed.
+    //
+    // # Returns
+    //
+    // * `Result<(), Error>` - Returns an error if there is no pending admin.
+    fn accept_admin(e: Env) -> Result<(), Error>;
+
+    // Get the current admin.
+    //
+    // # Returns
+    //
+    // * `Option<Address>` - The admin if the contract has been configured, otherwise `None`.
+    fn admin(e: Env) -> Option<Address>;
+
+    // Get the proposed admin.
+    //
+    // # Returns
+    //
+    // * `Option<Address>` - The pending admin if there is one, otherwise `None`.
+    fn pending_admin(e: Env) -> Option<Address>;
+
+    // Pause the contract, while it's paused new reverse domains can't be set but they can still be removed.
+    // It needs to be signed by the admin.
+    //
+    // # Arguments
+    //
+    // * `e` - The environment in which the contract is executed.
+    fn pause(e: Env);
+
+    // Unpause the contract, it needs to be signed by the admin.
+    //
+    // # Arguments
+    //
+    // * `e` - The environment in which the contract is executed.
+    fn unpause(e: Env);
+
+    // Check if the contract is paused.
+    //
+    // # Returns
+    //
+    // * `bool` - `true` if the contract is paused.
+    fn is_paused(e: Env) -> bool;
+
+    // Get the fee paid for adding or updating a reverse domain.
+    //
+    // # Returns
+    //
+    // * `Option<(i128, Address)>` - The fee and its currency if the contract has been configured, otherwise `None`.
+    fn fee(e: Env) -> Option<(i128, Address)>;
+
     // Set the reverse domain for an address.
+    // The labels can be in their unicode form (for example `Café`), they are saved in the canonical ascii form used by
+    // the registry (`xn--caf-dma`).
     //
     // # Arguments
     //
@@ -47,7 +114,7 @@ pub trait ReverseRegistrarTrait {
     //
     // # Returns
     //
-    // * `Result<(), Error>` - Returns an error if the operation fails.
+    // * `Result<(), Error>` - Returns an error if the operation fails (`InvalidName` if a label can't be converted).
     fn set(e: Env, address: Address, domain: Option<Domain>) -> Result<(), Error>;
 
     // Get the reverse domain for an address.
@@ -79,6 +146,11 @@ impl ReverseRegistrarTrait for ReverseRegistrar {
         bump_instance(&e);
         if let Some(current_admin) = get_admin(&e) {
             current_admin.require_auth();
+
+            // Once the admin is set it can only be changed with `propose_admin` and `accept_admin`
+            if current_admin != admin {
+                panic_with_error!(&e, Error::InvalidAdmin);
+            }
         }
         e.storage().instance().set(&CoreDataKeys::Admin, &admin);
         e.storage()
@@ -99,10 +171,65 @@ impl ReverseRegistrarTrait for ReverseRegistrar {
         e.deployer().update_current_contract_wasm(hash);
     }
 
+    fn propose_admin(e: Env, new_admin: Address) {
+        bump_instance(&e);
+        admin::propose_admin(&e, &get_admin(&e).unwrap(), &new_admin);
+    }
+
+    fn accept_admin(e: Env) -> Result<(), Error> {
+        bump_instance(&e);
+        let new_admin: Address =
+            admin::accept_admin(&e, &get_admin(&e).unwrap()).ok_or(Error::NoPendingAdmin)?;
+        e.storage().instance().set(&CoreDataKeys::Admin, &new_admin);
+        Ok(())
+    }
+
+    fn admin(e: Env) -> Option<Address> {
+        bump_instance(&e);
+        get_admin(&e)
+    }
+
+    fn pending_admin(e: Env) -> Option<Address> {
+        bump_instance(&e);
+        admin::pending_admin(&e)
+    }
+
+    fn pause(e: Env) {
+        bump_instance(&e);
+        get_admin(&e).unwrap().require_auth();
+        e.storage().instance().set(&CoreDataKeys::Paused, &true);
+    }
+
+    fn unpause(e: Env) {
+        bump_instance(&e);
+        get_admin(&e).unwrap().require_auth();
+        e.storage().instance().remove(&CoreDataKeys::Paused);
+    }
+
+    fn is_paused(e: Env) -> bool {
+        bump_instance(&e);
+        is_paused(&e)
+    }
+
+    fn fee(e: Env) -> Option<(i128, Address)> {
+        bump_instance(&e);
+        get_fee(&e)
+    }
+
     fn set(e: Env, address: Address, domain: Option<Domain>) -> Result<(), Error> {
         bump_instance(&e);
         address.require_auth();
 
+        // Removing a reverse domain is allowed while the contract is paused
+        if domain.is_some() && is_paused(&e) {
+            return Err(Error::ContractPaused);
+        }
+
+        let domain: Option<Domain> = match domain {
+            Some(domain) => Some(normalize_domain(&e, &domain)?),
+            None => None,
+        };
+
         let current_domain = e.storage().persistent().get::<Address, Domain>(&address);
         match (current_domain, domain) {
             // If both current and new domains exist and are equal
@@ -145,6 +272,21 @@ impl ReverseRegistrarTrait for ReverseRegistrar {
     }
 }
 
+// Converts the labels of the domain to the canonical ascii form used by the registry, so the reverse domain can be set
+// with the unicode name (for example `café.xlm` is saved as `xn--caf-dma.xlm`)
+fn normalize_domain(e: &Env, domain: &Domain) -> Result<Domain, Error> {
+    let mut subs: Vec<Bytes> = Vec::new(e);
+    for sub in domain.subs.iter() {
+        subs.push_back(label_to_ascii(&sub).map_err(|_| Error::InvalidName)?);
+    }
+
+    Ok(Domain {
+        tld: label_to_ascii(&domain.tld).map_err(|_| Error::InvalidName)?,
+        sld: label_to_ascii(&domain.sld).map_err(|_| Error::InvalidName)?,
+        subs,
+    })
+}
+
 fn validate_reverse_record(e: &Env, address: &Address, domain: &Domain) -> Result<(), Error> {
     let mut domain_node = generate_node(&e, &domain.sld, &domain.tld);
     if domain.subs.is_empty() {
@@ -157,17 +299,12 @@ fn validate_reverse_record(e: &Env, address: &Address, domain: &Domain) -> Resul
         };
     }
 
-    if domain.subs.len() != 1 {
-        // Only one subdomain is allowed.
-        // If registry allows more than one subdomain, this contract should be updated.
-        return Err(Error::NotImplemented);
+    // Subdomains are saved from the leaf to the root (`["a", "b"]` in `a.b.example.xlm`), so we generate the nodes
+    // starting from the last one
+    for sub in domain.subs.iter().rev() {
+        domain_node = generate_node(&e, &sub, &domain_node.try_into().unwrap());
     }
 
-    domain_node = generate_node(
-        &e,
-        &domain.subs.first().unwrap(),
-        &domain_node.try_into().unwrap(),
-    );
     let Record::SubDomain(subdomain) = fetch_domain_record(&e, &domain_node, true)? else {
         panic!("unreachable");
     };
@@ -217,10 +354,19 @@ fn get_admin(e: &Env) -> Option<Address> {
     e.storage().instance().get(&CoreDataKeys::Admin)
 }
 
+fn is_paused(e: &Env) -> bool {
+    e.storage().instance().has(&CoreDataKeys::Paused)
+}
+
+fn get_fee(e: &Env) -> Option<(i128, Address)> {
+    let fee: i128 = e.storage().instance().get(&CoreDataKeys::Fee)?;
+    let currency: Address = e.storage().instance().get(&CoreDataKeys::Currency)?;
+    Some((fee, currency))
+}
+
 fn pay_fee(e: &Env, caller: &Address) -> Result<(), Error> {
     let treasury: Address = e.storage().instance().get(&CoreDataKeys::Treasury).unwrap();
-    let currency: Address = e.storage().instance().get(&CoreDataKeys::Currency).unwrap();
-    let fee: i128 = e.storage().instance().get(&CoreDataKeys::Fee).unwrap();
+    let (fee, currency) = get_fee(&e).unwrap();
 
     let result = token::Client::new(&e, &currency).try_transfer(&caller, &treasury, &fee);
     if result.is_err() {
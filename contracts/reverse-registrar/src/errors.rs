@@ -8,4 +8,8 @@ pub enum Error {
     AddressMismatch = 2,
     NotImplemented = 3,
     FailedToPayFee = 4,
+    NoPendingAdmin = 5,
+    InvalidAdmin = 6,
+    ContractPaused = 7,
+    InvalidName = 8,
 }
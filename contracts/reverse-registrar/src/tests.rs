@@ -19,6 +19,7 @@ use crate::events::EventTopics;
 #[test]
 fn test_set_new_domain_with_domain() {
     let e: Env = create_env();
+    e.budget().reset_unlimited();
     let global_test_data: GlobalTestData = create_global_test_data(&e);
     let registry_test_data: registry::TestData = registry::create_test_data(&e);
     let reverse_registrar_test_data = reverse_registrar::create_test_data(&e);
@@ -108,6 +109,86 @@ fn test_set_new_domain_with_domain() {
     )
 }
 
+#[test]
+fn test_set_domain_with_unicode_labels() {
+    let e: Env = create_env();
+    e.budget().reset_unlimited();
+    let global_test_data: GlobalTestData = create_global_test_data(&e);
+    let registry_test_data: registry::TestData = registry::create_test_data(&e);
+    let reverse_registrar_test_data = reverse_registrar::create_test_data(&e);
+    registry::init_contract(&global_test_data, &registry_test_data);
+    reverse_registrar::init_contract(
+        &global_test_data,
+        &registry_test_data,
+        &reverse_registrar_test_data,
+    );
+
+    let owner: Address = Address::generate(&e);
+    let domain_address: Address = Address::generate(&e);
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+
+    global_test_data
+        .col_asset_stellar
+        .mock_all_auths()
+        .mint(&owner, &i128::MAX);
+    global_test_data
+        .gov_asset_stellar
+        .mock_all_auths()
+        .mint(&domain_address, &i128::MAX);
+
+    registry_test_data
+        .contract_client
+        .mock_all_auths()
+        .set_record(
+            &Bytes::from_slice(&e, "reversedemo".as_bytes()),
+            &tld,
+            &owner,
+            &domain_address,
+            &registry_test_data.min_duration,
+        );
+
+    // Labels that aren't valid UTF-8 can't be converted
+    assert_eq!(
+        reverse_registrar_test_data
+            .contract_client
+            .mock_all_auths()
+            .try_set(
+                &domain_address,
+                &Some(Domain {
+                    tld: tld.clone(),
+                    sld: Bytes::from_slice(&e, &[0x72, 0xFF]),
+                    subs: Vec::new(&e),
+                })
+            )
+            .unwrap_err()
+            .unwrap(),
+        Error::InvalidName.into()
+    );
+
+    // The name is saved in the canonical form used by the registry
+    reverse_registrar_test_data
+        .contract_client
+        .mock_all_auths()
+        .set(
+            &domain_address,
+            &Some(Domain {
+                tld: Bytes::from_slice(&e, "XLM".as_bytes()),
+                sld: Bytes::from_slice(&e, "ReverseDemo".as_bytes()),
+                subs: Vec::new(&e),
+            }),
+        );
+    assert_eq!(
+        reverse_registrar_test_data
+            .contract_client
+            .get(&domain_address),
+        Some(Domain {
+            tld,
+            sld: Bytes::from_slice(&e, "reversedemo".as_bytes()),
+            subs: Vec::new(&e),
+        })
+    );
+}
+
 #[test]
 fn test_set_new_domain_with_subdomain() {
     let e: Env = create_env();
@@ -412,6 +493,7 @@ fn test_set_new_domain_should_update() {
 #[test]
 fn test_remove_nonexistent_domain_should_do_nothing() {
     let e: Env = create_env();
+    e.budget().reset_unlimited();
     let global_test_data: GlobalTestData = create_global_test_data(&e);
     let registry_test_data: registry::TestData = registry::create_test_data(&e);
     let reverse_registrar_test_data = reverse_registrar::create_test_data(&e);
@@ -481,6 +563,7 @@ fn test_remove_nonexistent_domain_should_do_nothing() {
 #[test]
 fn test_address_missmatch_error_with_domain() {
     let e: Env = create_env();
+    e.budget().reset_unlimited();
     let global_test_data: GlobalTestData = create_global_test_data(&e);
     let registry_test_data: registry::TestData = registry::create_test_data(&e);
     let reverse_registrar_test_data = reverse_registrar::create_test_data(&e);
@@ -527,6 +610,7 @@ fn test_address_missmatch_error_with_domain() {
 #[test]
 fn test_address_missmatch_error_with_subdomain() {
     let e: Env = create_env();
+    e.budget().reset_unlimited();
     let global_test_data: GlobalTestData = create_global_test_data(&e);
     let registry_test_data: registry::TestData = registry::create_test_data(&e);
     let reverse_registrar_test_data = reverse_registrar::create_test_data(&e);
@@ -580,8 +664,9 @@ fn test_address_missmatch_error_with_subdomain() {
 }
 
 #[test]
-fn test_not_implemented_error() {
+fn test_set_new_domain_with_nested_subdomains() {
     let e: Env = create_env();
+    e.budget().reset_unlimited();
     let global_test_data: GlobalTestData = create_global_test_data(&e);
     let registry_test_data: registry::TestData = registry::create_test_data(&e);
     let reverse_registrar_test_data = reverse_registrar::create_test_data(&e);
@@ -607,40 +692,69 @@ fn test_not_implemented_error() {
         .mock_all_auths()
         .set_record(&domain, &tld, &owner, &owner, &duration);
 
-    let sub_domain: Bytes = Bytes::from_slice(&e, "payments".as_bytes());
     let domain_node: BytesN<32> = generate_node(&e, &domain, &tld);
+    let payments_sub: Bytes = Bytes::from_slice(&e, "payments".as_bytes());
+    let payments_node: BytesN<32> = generate_node(&e, &payments_sub, &domain_node.clone().into());
+    let hello_sub: Bytes = Bytes::from_slice(&e, "hello".as_bytes());
     let domain_address: Address = Address::generate(&e);
 
+    global_test_data
+        .gov_asset_stellar
+        .mock_all_auths()
+        .mint(&domain_address, &i128::MAX);
+
     registry_test_data.contract_client.mock_all_auths().set_sub(
-        &sub_domain,
+        &payments_sub,
         &RecordKeys::Record(domain_node.clone()),
+        &owner,
+    );
+    registry_test_data.contract_client.mock_all_auths().set_sub(
+        &hello_sub,
+        &RecordKeys::SubRecord(payments_node.clone()),
         &domain_address,
     );
 
+    // `hello.payments.reversedemo.xlm`
     let domain = Domain {
         tld,
         sld: domain.clone(),
-        subs: vec![
-            &e,
-            Bytes::from_slice(&e, "payments".as_bytes()),
-            Bytes::from_slice(&e, "hello".as_bytes()),
-        ],
+        subs: vec![&e, hello_sub.clone(), payments_sub.clone()],
+    };
+
+    reverse_registrar_test_data
+        .contract_client
+        .mock_all_auths()
+        .set(&domain_address, &Some(domain.clone()));
+
+    assert_eq!(
+        reverse_registrar_test_data
+            .contract_client
+            .get(&domain_address),
+        Some(domain.clone())
+    );
+
+    // Subdomains go from the leaf to the root, `payments.hello.reversedemo.xlm` does not exist
+    let wrong_order = Domain {
+        tld: domain.tld.clone(),
+        sld: domain.sld.clone(),
+        subs: vec![&e, payments_sub.clone(), hello_sub.clone()],
     };
 
     assert_eq!(
         reverse_registrar_test_data
             .contract_client
             .mock_all_auths()
-            .try_set(&owner, &Some(domain.clone()))
+            .try_set(&domain_address, &Some(wrong_order))
             .unwrap_err()
             .unwrap(),
-        Error::NotImplemented.into()
-    )
+        Error::FailedToGetRecord.into()
+    );
 }
 
 #[test]
 fn test_failed_to_get_record_with_domain() {
     let e: Env = create_env();
+    e.budget().reset_unlimited();
     let global_test_data: GlobalTestData = create_global_test_data(&e);
     let registry_test_data: registry::TestData = registry::create_test_data(&e);
     let reverse_registrar_test_data = reverse_registrar::create_test_data(&e);
@@ -680,6 +794,7 @@ fn test_failed_to_get_record_with_domain() {
 #[test]
 fn test_failed_to_get_record_error_with_subdomain() {
     let e: Env = create_env();
+    e.budget().reset_unlimited();
     let global_test_data: GlobalTestData = create_global_test_data(&e);
     let registry_test_data: registry::TestData = registry::create_test_data(&e);
     let reverse_registrar_test_data = reverse_registrar::create_test_data(&e);
@@ -725,6 +840,7 @@ fn test_failed_to_get_record_error_with_subdomain() {
 #[test]
 fn test_failed_to_pay_fee_error() {
     let e: Env = create_env();
+    e.budget().reset_unlimited();
     let global_test_data: GlobalTestData = create_global_test_data(&e);
     let registry_test_data: registry::TestData = registry::create_test_data(&e);
     let reverse_registrar_test_data = reverse_registrar::create_test_data(&e);
@@ -734,6 +850,13 @@ fn test_failed_to_pay_fee_error() {
         &registry_test_data,
         &reverse_registrar_test_data,
     );
+    assert_eq!(
+        reverse_registrar_test_data.contract_client.fee(),
+        Some((
+            reverse_registrar_test_data.fee,
+            global_test_data.gov_asset.clone()
+        ))
+    );
 
     let owner: Address = Address::generate(&e);
     let domain_address: Address = Address::generate(&e);
@@ -780,3 +903,139 @@ fn test_failed_to_pay_fee_error() {
         reverse_registrar_test_data.fee - 1
     )
 }
+
+#[test]
+fn test_admin_handover() {
+    let e: Env = create_env();
+    e.budget().reset_unlimited();
+    let global_test_data: GlobalTestData = create_global_test_data(&e);
+    let registry_test_data: registry::TestData = registry::create_test_data(&e);
+    let reverse_registrar_test_data = reverse_registrar::create_test_data(&e);
+    registry::init_contract(&global_test_data, &registry_test_data);
+    reverse_registrar::init_contract(
+        &global_test_data,
+        &registry_test_data,
+        &reverse_registrar_test_data,
+    );
+
+    let new_admin: Address = Address::generate(&e);
+
+    // The admin can't be replaced with `set_config`
+    assert!(reverse_registrar_test_data
+        .contract_client
+        .mock_all_auths()
+        .try_set_config(
+            &new_admin,
+            &registry_test_data.contract_client.address,
+            &reverse_registrar_test_data.fee,
+            &global_test_data.gov_asset,
+            &global_test_data.fee_taker,
+        )
+        .is_err());
+
+    assert_eq!(
+        reverse_registrar_test_data
+            .contract_client
+            .mock_all_auths()
+            .try_accept_admin()
+            .unwrap_err()
+            .unwrap(),
+        Error::NoPendingAdmin.into()
+    );
+
+    reverse_registrar_test_data
+        .contract_client
+        .mock_all_auths()
+        .propose_admin(&new_admin);
+    assert_eq!(e.auths().first().unwrap().0, global_test_data.adm);
+    assert_eq!(
+        reverse_registrar_test_data.contract_client.pending_admin(),
+        Some(new_admin.clone())
+    );
+
+    reverse_registrar_test_data
+        .contract_client
+        .mock_all_auths()
+        .accept_admin();
+    assert_eq!(e.auths().first().unwrap().0, new_admin);
+    assert_eq!(
+        reverse_registrar_test_data.contract_client.admin(),
+        Some(new_admin.clone())
+    );
+    assert_eq!(
+        reverse_registrar_test_data.contract_client.pending_admin(),
+        None
+    );
+}
+
+#[test]
+fn test_pause() {
+    let e: Env = create_env();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    let global_test_data: GlobalTestData = create_global_test_data(&e);
+    let registry_test_data: registry::TestData = registry::create_test_data(&e);
+    let reverse_registrar_test_data = reverse_registrar::create_test_data(&e);
+    registry::init_contract(&global_test_data, &registry_test_data);
+    reverse_registrar::init_contract(
+        &global_test_data,
+        &registry_test_data,
+        &reverse_registrar_test_data,
+    );
+
+    let owner: Address = Address::generate(&e);
+    let domain: Bytes = Bytes::from_slice(&e, "reversedemo".as_bytes());
+    let tld: Bytes = Bytes::from_slice(&e, "xlm".as_bytes());
+
+    global_test_data.col_asset_stellar.mint(&owner, &i128::MAX);
+    global_test_data.gov_asset_stellar.mint(&owner, &i128::MAX);
+
+    registry_test_data.contract_client.set_record(
+        &domain,
+        &tld,
+        &owner,
+        &owner,
+        &registry_test_data.min_duration,
+    );
+
+    let domain = Domain {
+        tld,
+        sld: domain,
+        subs: vec![&e],
+    };
+
+    reverse_registrar_test_data
+        .contract_client
+        .set(&owner, &Some(domain.clone()));
+
+    reverse_registrar_test_data.contract_client.pause();
+    assert_eq!(e.auths().first().unwrap().0, global_test_data.adm);
+    assert!(reverse_registrar_test_data.contract_client.is_paused());
+
+    assert_eq!(
+        reverse_registrar_test_data
+            .contract_client
+            .try_set(&owner, &Some(domain.clone()))
+            .unwrap_err()
+            .unwrap(),
+        Error::ContractPaused.into()
+    );
+
+    // Removing the reverse domain is still allowed
+    reverse_registrar_test_data
+        .contract_client
+        .set(&owner, &None);
+    assert_eq!(
+        reverse_registrar_test_data.contract_client.get(&owner),
+        None
+    );
+
+    reverse_registrar_test_data.contract_client.unpause();
+    reverse_registrar_test_data
+        .contract_client
+        .set(&owner, &Some(domain.clone()));
+    assert_eq!(
+        reverse_registrar_test_data.contract_client.get(&owner),
+        Some(domain)
+    );
+}
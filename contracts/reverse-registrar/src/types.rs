@@ -12,7 +12,7 @@ pub struct Domain {
     pub sld: Bytes,
 
     // A list of subdomains, e.g., ["a", "b"] in `a.b.example.xlm`.
-    // Currently, the maximum number of subdomains is 1, but more can be added in the future.
+    // The max number of subdomains is defined by the registry.
     // Keep it empty if there are no subdomains.
     pub subs: Vec<Bytes>,
 }
@@ -24,4 +24,5 @@ pub enum CoreDataKeys {
     Fee,
     Currency,
     Treasury,
+    Paused,
 }
@@ -1,6 +1,11 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env};
+mod errors;
+mod tests;
+
+use crate::errors::ContractErrors;
+use common::admin;
+use soroban_sdk::{contract, contractimpl, contracttype, panic_with_error, Address, BytesN, Env};
 
 #[contracttype]
 pub enum CoreDataKeys {
@@ -10,6 +15,12 @@ pub enum CoreDataKeys {
 pub trait TreasuryContractTrait {
     fn init(e: Env, adm: Address);
     fn upgrade(e: Env, hash: BytesN<32>);
+
+    // The admin is transferred in two steps: the current admin proposes a new admin and the new admin accepts it
+    fn propose_admin(e: Env, new_admin: Address);
+    fn accept_admin(e: Env);
+    fn admin(e: Env) -> Address;
+    fn pending_admin(e: Env) -> Option<Address>;
 }
 
 #[contract]
@@ -38,4 +49,37 @@ impl TreasuryContractTrait for TreasuryContract {
 
         e.deployer().update_current_contract_wasm(hash);
     }
+
+    fn propose_admin(e: Env, new_admin: Address) {
+        let adm: Address = e
+            .storage()
+            .instance()
+            .get::<CoreDataKeys, Address>(&CoreDataKeys::Adm)
+            .unwrap();
+
+        admin::propose_admin(&e, &adm, &new_admin);
+    }
+
+    fn accept_admin(e: Env) {
+        let adm: Address = e
+            .storage()
+            .instance()
+            .get::<CoreDataKeys, Address>(&CoreDataKeys::Adm)
+            .unwrap();
+
+        let new_adm: Address = admin::accept_admin(&e, &adm)
+            .unwrap_or_else(|| panic_with_error!(&e, &ContractErrors::NoPendingAdmin));
+        e.storage().instance().set(&CoreDataKeys::Adm, &new_adm);
+    }
+
+    fn admin(e: Env) -> Address {
+        e.storage()
+            .instance()
+            .get::<CoreDataKeys, Address>(&CoreDataKeys::Adm)
+            .unwrap()
+    }
+
+    fn pending_admin(e: Env) -> Option<Address> {
+        admin::pending_admin(&e)
+    }
 }
@@ -0,0 +1,49 @@
+#![cfg(test)]
+
+use crate::errors::ContractErrors;
+use crate::{TreasuryContract, TreasuryContractClient};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{Address, Env};
+
+#[test]
+fn test_admin_handover() {
+    let e: Env = Env::default();
+    let adm: Address = Address::generate(&e);
+    let new_adm: Address = Address::generate(&e);
+    let contract_client: TreasuryContractClient =
+        TreasuryContractClient::new(&e, &e.register_contract(None, TreasuryContract));
+
+    contract_client.init(&adm);
+    assert!(contract_client.try_init(&new_adm).is_err());
+
+    assert_eq!(
+        contract_client
+            .mock_all_auths()
+            .try_accept_admin()
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NoPendingAdmin.into()
+    );
+
+    // Only the current admin can propose a new admin
+    assert!(contract_client.try_propose_admin(&new_adm).is_err());
+
+    contract_client.mock_all_auths().propose_admin(&new_adm);
+    assert_eq!(e.auths().first().unwrap().0, adm);
+    assert_eq!(contract_client.admin(), adm);
+    assert_eq!(contract_client.pending_admin(), Some(new_adm.clone()));
+
+    contract_client.mock_all_auths().accept_admin();
+    assert_eq!(e.auths().first().unwrap().0, new_adm);
+    assert_eq!(contract_client.admin(), new_adm);
+    assert_eq!(contract_client.pending_admin(), None);
+
+    assert_eq!(
+        contract_client
+            .mock_all_auths()
+            .try_accept_admin()
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NoPendingAdmin.into()
+    );
+}
@@ -145,12 +145,16 @@ pub mod registry {
         test_data
             .contract_client
             .mock_all_auths()
-            .set_offers_config(&global_test_data.fee_taker, &test_data.offer_fee);
+            .set_offers_config(
+                &global_test_data.adm,
+                &global_test_data.fee_taker,
+                &test_data.offer_fee,
+            );
 
         test_data
             .contract_client
             .mock_all_auths()
-            .set_oracle(&global_test_data.oracle_addr);
+            .set_oracle(&global_test_data.adm, &global_test_data.oracle_addr);
     }
 }
 